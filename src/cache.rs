@@ -0,0 +1,270 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::UNIX_EPOCH;
+
+use crate::utils::{self, CommandOutput};
+
+/// The file within the cache directory holding the cached versions
+const VERSIONS_FILE: &str = "versions.json";
+
+/// Serializes the updates of the cache by the threads of one process
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Identifies the output of a command by the binary it runs and the environment
+/// variables affecting its output. An upgraded binary changes its modification
+/// time or size, so that its version is reported anew.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct CacheKey {
+    path: PathBuf,
+    args: Vec<String>,
+    modified: (u64, u32),
+    size: u64,
+    env: BTreeMap<String, String>,
+    /// The project's settings selecting the version of a tool which switches
+    /// versions itself, like the `toolchain` of a `go.mod`
+    #[serde(default)]
+    project_settings: Option<String>,
+}
+
+impl CacheKey {
+    fn new(
+        cmd: &str,
+        args: &[&str],
+        env_vars: &[&str],
+        project_settings: Option<String>,
+    ) -> Option<CacheKey> {
+        let path = resolve_binary(cmd)?;
+        let metadata = fs::metadata(&path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+
+        Some(CacheKey {
+            path,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            modified: (modified.as_secs(), modified.subsec_nanos()),
+            size: metadata.len(),
+            env: env_vars
+                .iter()
+                .filter_map(|name| Some((name.to_string(), env::var(name).ok()?)))
+                .collect(),
+            project_settings,
+        })
+    }
+
+    /// Whether both keys run the same binary in the same way, though the binary
+    /// may have changed since
+    fn is_same_command(&self, other: &CacheKey) -> bool {
+        self.path == other.path
+            && self.args == other.args
+            && self.env == other.env
+            && self.project_settings == other.project_settings
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    key: CacheKey,
+    output: CommandOutput,
+}
+
+/// The directory starship caches data in: `$XDG_CACHE_HOME/starship`, or the
+/// platform's cache directory when `XDG_CACHE_HOME` isn't set
+pub fn cache_dir() -> Option<PathBuf> {
+    let cache_home = env::var_os("XDG_CACHE_HOME")
+        .filter(|cache_home| !cache_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::cache_dir)?;
+    Some(cache_home.join("starship"))
+}
+
+/// Get the output of a command from the cache, running it with `exec` when it
+/// isn't cached yet. Only successful outputs are cached.
+pub fn cached_output<F>(
+    cmd: &str,
+    args: &[&str],
+    env_vars: &[&str],
+    project_settings: Option<String>,
+    exec: F,
+) -> Option<CommandOutput>
+where
+    F: FnOnce() -> Option<CommandOutput>,
+{
+    let key = match CacheKey::new(cmd, args, env_vars, project_settings) {
+        Some(key) => key,
+        None => {
+            log::debug!("Unable to find the binary of {:?} to cache its output", cmd);
+            return exec();
+        }
+    };
+    if is_toolchain_shim(&key.path) {
+        log::trace!("Not caching the output of the shim {:?}", key.path);
+        return exec();
+    }
+
+    let entries = read_entries();
+    if let Some(entry) = entries.iter().find(|entry| entry.key == key) {
+        log::trace!("Using the cached output of {:?}", key.path);
+        return Some(entry.output.clone());
+    }
+
+    let output = exec()?;
+    if output.success {
+        let entry = CacheEntry {
+            key,
+            output: output.clone(),
+        };
+        if let Err(error) = add_entry(entry) {
+            log::debug!("Unable to write the version cache: {}", error);
+        }
+    }
+    Some(output)
+}
+
+/// Add an entry to the cache. The version modules of a prompt run in parallel,
+/// so they take turns updating the cache, each re-reading it to keep the
+/// entries added since. Prompts rendered at the same time in other shells may
+/// still overwrite each other's entries, which are then cached again later on.
+fn add_entry(entry: CacheEntry) -> io::Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // An outdated output of the same command is replaced, so that the cache
+    // doesn't grow with every upgrade of a tool
+    let mut entries = read_entries();
+    entries.retain(|cached| !cached.key.is_same_command(&entry.key));
+    entries.push(entry);
+    write_entries(&entries)
+}
+
+/// Remove all cached versions
+pub fn clear() -> io::Result<()> {
+    let path = match cache_dir() {
+        Some(cache_dir) => cache_dir.join(VERSIONS_FILE),
+        None => return Ok(()),
+    };
+
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+fn read_entries() -> Vec<CacheEntry> {
+    let content = match cache_dir().map(|cache_dir| utils::read_file(cache_dir.join(VERSIONS_FILE)))
+    {
+        Some(Ok(content)) => content,
+        _ => return Vec::new(),
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|error| {
+        log::debug!("Ignoring the invalid version cache: {}", error);
+        Vec::new()
+    })
+}
+
+fn write_entries(entries: &[CacheEntry]) -> io::Result<()> {
+    let cache_dir =
+        cache_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cache directory"))?;
+    fs::create_dir_all(&cache_dir)?;
+
+    // Other processes may be reading the cache, so it's replaced in one step
+    // rather than written in place. Writers within a process hold `WRITE_LOCK`,
+    // so the process id makes the temporary file unique to its writer.
+    let temp_path = cache_dir.join(format!("{}.{}", VERSIONS_FILE, std::process::id()));
+    fs::write(&temp_path, serde_json::to_string(entries)?)?;
+    fs::rename(temp_path, cache_dir.join(VERSIONS_FILE))
+}
+
+/// Whether the binary is a shim of a toolchain manager such as rustup, pyenv or
+/// rbenv. A shim never changes, while the version it runs depends on the
+/// manager's settings and on version files in the current directory or above.
+fn is_toolchain_shim(path: &Path) -> bool {
+    let home_dir = dirs::home_dir().unwrap_or_default();
+    let env_dir = |name: &str, default: &str| {
+        env::var_os(name)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home_dir.join(default))
+    };
+    let shim_dirs = [
+        env_dir("CARGO_HOME", ".cargo").join("bin"),
+        env_dir("PYENV_ROOT", ".pyenv").join("shims"),
+        env_dir("RBENV_ROOT", ".rbenv").join("shims"),
+    ];
+
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    if shim_dirs.iter().any(|dir| dir == parent) || parent.ends_with("shims") {
+        return true;
+    }
+
+    // rustup installs its proxies as links to itself, while pyenv and rbenv
+    // shims may be linked from elsewhere
+    let target = fs::canonicalize(path).unwrap_or_default();
+    let is_manager = match target.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => ["rustup", "pyenv", "rbenv"].contains(&stem),
+        None => false,
+    };
+    is_manager || matches!(target.parent(), Some(dir) if dir.ends_with("shims"))
+}
+
+/// Find the binary a command runs, searching `PATH` unless given a path
+fn resolve_binary(cmd: &str) -> Option<PathBuf> {
+    let cmd_path = Path::new(cmd);
+    if cmd_path.components().count() > 1 {
+        return Some(cmd_path.to_path_buf()).filter(|path| path.is_file());
+    }
+
+    let executable_names = if cfg!(windows) {
+        vec![cmd.to_string(), format!("{}.exe", cmd)]
+    } else {
+        vec![cmd.to_string()]
+    };
+
+    env::split_paths(&env::var_os("PATH")?).find_map(|dir| {
+        executable_names
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_binary_from_path() {
+        assert!(resolve_binary("sh").is_some());
+        assert_eq!(resolve_binary("not-a-real-command-anywhere"), None);
+        assert_eq!(resolve_binary("/bin/sh"), Some(PathBuf::from("/bin/sh")));
+    }
+
+    #[test]
+    fn toolchain_shims() {
+        let home_dir = dirs::home_dir().unwrap();
+        assert!(is_toolchain_shim(&home_dir.join(".pyenv/shims/python")));
+        assert!(is_toolchain_shim(Path::new("/opt/asdf/shims/ruby")));
+        assert!(!is_toolchain_shim(Path::new("/bin/sh")));
+    }
+
+    #[test]
+    fn same_command_with_changed_binary() {
+        let key = CacheKey::new("sh", &["--version"], &["PATH"], None).unwrap();
+        let upgraded = CacheKey {
+            size: key.size + 1,
+            ..key.clone()
+        };
+        assert_ne!(key, upgraded);
+        assert!(key.is_same_command(&upgraded));
+
+        let other_args = CacheKey {
+            args: Vec::new(),
+            ..key.clone()
+        };
+        assert!(!key.is_same_command(&other_args));
+    }
+}
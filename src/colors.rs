@@ -0,0 +1,390 @@
+use ansi_term::{ANSIString, Color, Style};
+use std::env;
+
+/// Values of `TERM` for terminals that can only display 16 colors
+const LIMITED_TERMS: &[&str] = &["linux", "vt100", "ansi", "dumb", "xterm"];
+
+/// The number of colors a terminal can display
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorDepth {
+    /// 24-bit RGB colors
+    TrueColor,
+
+    /// The 256 colors of the xterm palette
+    Ansi256,
+
+    /// The 8 basic colors and their bright variants
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Find the color depth of the given name, as set in `color_depth`
+    pub fn from_name(name: &str) -> Option<ColorDepth> {
+        match name {
+            "truecolor" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" => Some(ColorDepth::Ansi16),
+            _ => None,
+        }
+    }
+
+    /// Detect the color depth of the terminal from `COLORTERM` and `TERM`
+    pub fn detect() -> ColorDepth {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        ColorDepth::from_env(&colorterm, &term)
+    }
+
+    fn from_env(colorterm: &str, term: &str) -> ColorDepth {
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            return ColorDepth::TrueColor;
+        }
+        if term.contains("256color") {
+            return ColorDepth::Ansi256;
+        }
+
+        // Only terminals known to be limited to 16 colors are downgraded. Others,
+        // like tmux's `screen` or `xterm-kitty`, and a missing `TERM`, e.g. in the
+        // consoles of Windows, have their colors left as they are.
+        if LIMITED_TERMS.contains(&term) {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::TrueColor
+        }
+    }
+
+    /// Map the colors of a style to the nearest ones the terminal can display
+    pub fn downgrade_style(self, style: Style) -> Style {
+        Style {
+            foreground: style.foreground.map(|color| self.downgrade(color)),
+            background: style.background.map(|color| self.downgrade(color)),
+            ..style
+        }
+    }
+
+    /// Map the colors of styled strings to the nearest ones the terminal can display
+    pub fn downgrade_strings<'a>(self, strings: Vec<ANSIString<'a>>) -> Vec<ANSIString<'a>> {
+        if self == ColorDepth::TrueColor {
+            return strings;
+        }
+
+        strings
+            .into_iter()
+            .map(|string| {
+                let style = self.downgrade_style(*string.style_ref());
+                style.paint((*string).to_string())
+            })
+            .collect()
+    }
+
+    /// Map a color to the nearest one the terminal can display
+    pub fn downgrade(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::Ansi256, Color::RGB(r, g, b)) => nearest_256((r, g, b)),
+            (ColorDepth::Ansi16, Color::RGB(r, g, b)) => nearest_16((r, g, b)),
+            (ColorDepth::Ansi16, Color::Fixed(number)) if number > 15 => {
+                nearest_16(fixed_to_rgb(number))
+            }
+            (_, color) => color,
+        }
+    }
+}
+
+/// The levels of each component in the 6x6x6 color cube of the 256 colors
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB values xterm uses for the 16 basic colors
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The nearest color of the 256 colors, from either the color cube or the grays
+fn nearest_256(rgb: (u8, u8, u8)) -> Color {
+    let cube_index = |component: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&index| (i32::from(CUBE_LEVELS[index]) - i32::from(component)).abs())
+            .unwrap_or(0) as u8
+    };
+    let (r, g, b) = (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    // The 24 grays run from 8 to 238 in steps of 10
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(rgb, fixed_to_rgb(gray)) < distance(rgb, fixed_to_rgb(cube)) {
+        Color::Fixed(gray)
+    } else {
+        Color::Fixed(cube)
+    }
+}
+
+/// The nearest of the 16 basic colors. The basic colors are named where
+/// possible, so that they follow the terminal's theme.
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    let index = (0..BASIC_COLORS.len())
+        .min_by_key(|&index| distance(rgb, BASIC_COLORS[index]))
+        .unwrap_or(0) as u8;
+
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Purple,
+        6 => Color::Cyan,
+        7 => Color::White,
+        index => Color::Fixed(index),
+    }
+}
+
+/// The RGB values of one of the 256 colors
+fn fixed_to_rgb(number: u8) -> (u8, u8, u8) {
+    match number {
+        0..=15 => BASIC_COLORS[usize::from(number)],
+        16..=231 => {
+            let index = number - 16;
+            (
+                CUBE_LEVELS[usize::from(index / 36)],
+                CUBE_LEVELS[usize::from(index / 6 % 6)],
+                CUBE_LEVELS[usize::from(index % 6)],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (number - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// The squared distance between two colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let component = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+    component(a.0, b.0) + component(a.1, b.1) + component(a.2, b.2)
+}
+
+/// Look up a CSS named color, e.g. `rebeccapurple`
+pub fn css_color(name: &str) -> Option<Color> {
+    CSS_COLORS
+        .iter()
+        .find(|(css_name, _)| *css_name == name)
+        .map(|(_, (r, g, b))| Color::RGB(*r, *g, *b))
+}
+
+/// The named colors of CSS. The names of the standard terminal colors (`black`,
+/// `red`, `green`, `yellow`, `blue`, `purple`, `cyan` and `white`) are left out,
+/// as they refer to the colors of the terminal's theme instead.
+const CSS_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("magenta", (255, 0, 255)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("wheat", (245, 222, 179)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_color_depth() {
+        assert_eq!(
+            ColorDepth::from_env("truecolor", "xterm-256color"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", "xterm-256color"),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env("", "linux"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "xterm"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "dumb"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "screen"), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env("", "tmux"), ColorDepth::TrueColor);
+        assert_eq!(
+            ColorDepth::from_env("", "xterm-kitty"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(ColorDepth::from_env("", "alacritty"), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env("", ""), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn downgrade_to_256_colors() {
+        let depth = ColorDepth::Ansi256;
+        assert_eq!(depth.downgrade(Color::RGB(255, 135, 0)), Color::Fixed(208));
+        assert_eq!(depth.downgrade(Color::RGB(0, 0, 0)), Color::Fixed(16));
+        assert_eq!(
+            depth.downgrade(Color::RGB(128, 128, 128)),
+            Color::Fixed(244)
+        );
+        assert_eq!(depth.downgrade(Color::Fixed(208)), Color::Fixed(208));
+        assert_eq!(depth.downgrade(Color::Red), Color::Red);
+    }
+
+    #[test]
+    fn downgrade_to_16_colors() {
+        let depth = ColorDepth::Ansi16;
+        assert_eq!(depth.downgrade(Color::RGB(200, 10, 10)), Color::Red);
+        assert_eq!(depth.downgrade(Color::RGB(250, 250, 250)), Color::Fixed(15));
+        assert_eq!(depth.downgrade(Color::Fixed(196)), Color::Fixed(9));
+        assert_eq!(depth.downgrade(Color::Fixed(3)), Color::Fixed(3));
+    }
+
+    #[test]
+    fn truecolor_is_unchanged() {
+        let color = Color::RGB(1, 2, 3);
+        assert_eq!(ColorDepth::TrueColor.downgrade(color), color);
+    }
+}
@@ -1,15 +1,24 @@
+use crate::colors;
 use crate::utils;
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use dirs::home_dir;
 use toml::value::Table;
 use toml::value::Value;
 
 use ansi_term::Color;
+use once_cell::sync::OnceCell;
+
+/// The colors of the palette selected with `palette`, which style strings can
+/// refer to by name. It is loaded once, along with the configuration.
+static PALETTE: OnceCell<HashMap<String, Color>> = OnceCell::new();
 
 pub trait Config {
-    fn initialize() -> Table;
-    fn config_from_file() -> Option<Table>;
+    fn initialize(current_dir: &Path) -> Table;
+    fn config_from_file(file_path: &Path) -> Option<Table>;
     fn get_module_config(&self, module_name: &str) -> Option<&Table>;
 
     // Config accessor methods
@@ -17,48 +26,51 @@ pub trait Config {
     fn get_as_str(&self, key: &str) -> Option<&str>;
     fn get_as_i64(&self, key: &str) -> Option<i64>;
     fn get_as_array(&self, key: &str) -> Option<&Vec<Value>>;
-    fn get_as_ansi_style(&self, key: &str) -> Option<ansi_term::Style>;
 
     // Internal implementation for accessors
     fn get_config(&self, key: &str) -> Option<&Value>;
 }
 
 impl Config for Table {
-    /// Initialize the Config struct
-    fn initialize() -> Table {
-        if let Some(file_data) = Self::config_from_file() {
-            return file_data;
+    /// Initialize the Config struct, merging the configuration files which
+    /// apply to the current directory
+    fn initialize(current_dir: &Path) -> Table {
+        let mut config = Table::new();
+        for (layer, file_path) in config_files(current_dir) {
+            if let Some(layer_config) = Self::config_from_file(&file_path) {
+                merge_layer(&mut config, layer, layer_config);
+            }
         }
-        Self::new()
-    }
 
-    /// Create a config from a starship configuration file
-    fn config_from_file() -> Option<Table> {
-        let file_path = if let Ok(path) = env::var("STARSHIP_CONFIG") {
-            // Use $STARSHIP_CONFIG as the config path if available
-            log::debug!("STARSHIP_CONFIG is set: \n{}", &path);
-            path
-        } else {
-            // Default to using ~/.config/starship.toml
-            log::debug!("STARSHIP_CONFIG is not set");
-            let config_path = home_dir()?.join(".config/starship.toml");
-            let config_path_str = config_path.to_str()?.to_owned();
-            log::debug!("Using default config path: {}", config_path_str);
-            config_path_str
-        };
+        apply_profiles(&mut config, &ProfileEnvironment::detect());
+        load_palette(&config);
+        config
+    }
 
-        let toml_content = match utils::read_file(&file_path) {
-            Ok(content) => {
-                log::trace!("Config file content: \n{}", &content);
-                Some(content)
+    /// Create a config from a starship configuration file, merged with the
+    /// files it includes
+    fn config_from_file(file_path: &Path) -> Option<Table> {
+        log::debug!("Reading config file: {}", file_path.display());
+        let config_files = match read_config_files(file_path) {
+            Ok(config_files) => config_files,
+            // A missing configuration file is fine, unlike a missing included file
+            Err(ConfigError::Read(ref path, ref error)) if path == file_path => {
+                log::debug!("Unable to read config file content: \n{}", error);
+                return None;
             }
-            Err(e) => {
-                log::debug!("Unable to read config file content: \n{}", &e);
-                None
+            Err(error) => {
+                log::warn!(
+                    "Unable to load config file (run `starship config check` for details): {}",
+                    error
+                );
+                return None;
             }
-        }?;
+        };
 
-        let config = toml::from_str(&toml_content).ok()?;
+        let mut config = Table::new();
+        for (_, file_config) in config_files {
+            merge_tables(&mut config, file_config);
+        }
         log::debug!("Config parsed: \n{:?}", &config);
         Some(config)
     }
@@ -71,8 +83,14 @@ impl Config for Table {
         value
     }
 
-    /// Get the subset of the table for a module by its name
+    /// Get the subset of the table for a module by its name. Dotted names refer to
+    /// nested tables, e.g. `custom.foo` to `[custom.foo]`.
     fn get_module_config(&self, key: &str) -> Option<&Table> {
+        let mut parts = key.splitn(2, '.');
+        if let (Some(parent), Some(child)) = (parts.next(), parts.next()) {
+            return self.get_module_config(parent)?.get_module_config(child);
+        }
+
         log::trace!("Looking for module key \"{}\"", key);
         let value = self.get(key);
         log_if_key_found(key, value);
@@ -130,12 +148,335 @@ impl Config for Table {
             casted
         })
     }
+}
 
-    /// Get a text key and attempt to interpret it into an ANSI style.
-    fn get_as_ansi_style(&self, key: &str) -> Option<ansi_term::Style> {
-        // TODO: This should probably not unwrap to an empty new Style but inform the user about the problem
-        self.get_as_str(key)
-            .map(|x| parse_style_string(x).unwrap_or_default())
+/// Get the path of the user's configuration file: `$STARSHIP_CONFIG` if it is
+/// set, otherwise `starship.toml` in `$XDG_CONFIG_HOME` (`~/.config` by default)
+pub fn get_config_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var("STARSHIP_CONFIG") {
+        // Use $STARSHIP_CONFIG as the config path if available
+        log::debug!("STARSHIP_CONFIG is set: \n{}", &path);
+        Some(PathBuf::from(path))
+    } else {
+        log::debug!("STARSHIP_CONFIG is not set");
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|config_home| !config_home.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| Some(home_dir()?.join(".config")))?;
+        let config_path = config_home.join("starship.toml");
+        log::debug!("Using default config path: {}", config_path.display());
+        Some(config_path)
+    }
+}
+
+/// The layers of the configuration, from the lowest to the highest precedence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigLayer {
+    /// `starship.toml` in `$XDG_CONFIG_DIRS` (`/etc/xdg` by default), shared by
+    /// all users of the system
+    System,
+
+    /// The user's configuration file, see `get_config_path`
+    User,
+
+    /// `.starship.toml` in the current directory or one of its parents
+    Directory,
+}
+
+/// The name of the configuration files applying to a directory and its children
+const DIRECTORY_CONFIG_FILE: &str = ".starship.toml";
+
+/// List the configuration files which apply to the current directory, from the
+/// lowest to the highest precedence. An explicit `$STARSHIP_CONFIG` is always
+/// listed, while other files are only listed when they exist.
+pub fn config_files(current_dir: &Path) -> Vec<(ConfigLayer, PathBuf)> {
+    let mut config_files = Vec::new();
+
+    // The first of `$XDG_CONFIG_DIRS` is the most important
+    let config_dirs = env::var_os("XDG_CONFIG_DIRS")
+        .filter(|config_dirs| !config_dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".into());
+    let mut system_files = env::split_paths(&config_dirs)
+        .map(|config_dir| config_dir.join("starship.toml"))
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    system_files.reverse();
+    config_files.extend(
+        system_files
+            .into_iter()
+            .map(|path| (ConfigLayer::System, path)),
+    );
+
+    if let Some(path) = get_config_path() {
+        if path.is_file() || env::var_os("STARSHIP_CONFIG").is_some() {
+            config_files.push((ConfigLayer::User, path));
+        }
+    }
+
+    // Files closer to the current directory are more important
+    let mut directory_files = current_dir
+        .ancestors()
+        .map(|dir| dir.join(DIRECTORY_CONFIG_FILE))
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    directory_files.reverse();
+    config_files.extend(
+        directory_files
+            .into_iter()
+            .map(|path| (ConfigLayer::Directory, path)),
+    );
+
+    config_files
+}
+
+/// A configuration file which couldn't be loaded
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file couldn't be read
+    Read(PathBuf, std::io::Error),
+
+    /// The file isn't valid TOML
+    Syntax(PathBuf, toml::de::Error),
+
+    /// The `include` of the file is invalid, or includes the file itself
+    Include(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, error) => write!(
+                f,
+                "{}: unable to read the configuration file: {}",
+                path.display(),
+                error
+            ),
+            ConfigError::Syntax(path, error) => {
+                write!(f, "{}: syntax error: {}", path.display(), error)
+            }
+            ConfigError::Include(path, message) => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+/// Read a configuration file and the files it includes with `include`, listing
+/// them in the order they are merged in: each file comes after the files it
+/// includes, so that it takes precedence over them.
+pub fn read_config_files(file_path: &Path) -> Result<Vec<(PathBuf, Table)>, ConfigError> {
+    let mut config_files = Vec::new();
+    read_included_files(file_path, &mut Vec::new(), &mut config_files)?;
+    Ok(config_files)
+}
+
+/// Read a file and the files it includes. `including` holds the chain of files
+/// which led to this one, in order to detect cycles.
+fn read_included_files(
+    file_path: &Path,
+    including: &mut Vec<PathBuf>,
+    config_files: &mut Vec<(PathBuf, Table)>,
+) -> Result<(), ConfigError> {
+    let read_error = |error| ConfigError::Read(file_path.to_path_buf(), error);
+    let canonical_path = file_path.canonicalize().map_err(read_error)?;
+
+    if including.contains(&canonical_path) {
+        let chain = including
+            .iter()
+            .chain(std::iter::once(&canonical_path))
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+        let including_file = including.last().cloned().unwrap_or_default();
+        return Err(ConfigError::Include(
+            including_file,
+            format!("include cycle: {}", chain.join(" -> ")),
+        ));
+    }
+
+    let content = utils::read_file(file_path).map_err(read_error)?;
+    log::trace!("Config file content: \n{}", &content);
+    let mut config: Table = toml::from_str(&content)
+        .map_err(|error| ConfigError::Syntax(file_path.to_path_buf(), error))?;
+
+    let include = match config.remove("include") {
+        Some(include) => include_paths(file_path, &include)
+            .map_err(|message| ConfigError::Include(file_path.to_path_buf(), message))?,
+        None => Vec::new(),
+    };
+
+    including.push(canonical_path);
+    for included_path in include {
+        read_included_files(&included_path, including, config_files)?;
+    }
+    including.pop();
+
+    config_files.push((file_path.to_path_buf(), config));
+    Ok(())
+}
+
+/// Resolve the paths of an `include` array. `~` is expanded to the home directory,
+/// and relative paths are relative to the directory of the including file.
+fn include_paths(file_path: &Path, include: &Value) -> Result<Vec<PathBuf>, String> {
+    let invalid_include = || {
+        format!(
+            "\"include\" should be an array of paths, found {}",
+            include.type_str()
+        )
+    };
+    let base_dir = file_path.parent().unwrap_or_else(|| Path::new(""));
+
+    include
+        .as_array()
+        .ok_or_else(invalid_include)?
+        .iter()
+        .map(|path| {
+            let path = path.as_str().ok_or_else(invalid_include)?;
+            let path = match path.strip_prefix("~/") {
+                Some(path) => home_dir()
+                    .ok_or_else(|| "unable to find the home directory".to_string())?
+                    .join(path),
+                None => PathBuf::from(path),
+            };
+            Ok(base_dir.join(path))
+        })
+        .collect()
+}
+
+/// The facts about the environment which the `when` rules of profiles are
+/// matched against
+#[derive(Debug, Default)]
+pub struct ProfileEnvironment {
+    /// The profile selected with `STARSHIP_PROFILE`, which replaces the rules
+    pub selected: Option<String>,
+    pub hostname: String,
+    pub is_ssh: bool,
+    pub is_container: bool,
+}
+
+impl ProfileEnvironment {
+    pub fn detect() -> ProfileEnvironment {
+        let non_empty_var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+
+        ProfileEnvironment {
+            selected: non_empty_var("STARSHIP_PROFILE"),
+            hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+            is_ssh: non_empty_var("SSH_CONNECTION").is_some(),
+            // Docker and Podman leave a file behind, while systemd-nspawn and
+            // Podman set `container`
+            is_container: Path::new("/.dockerenv").exists()
+                || Path::new("/run/.containerenv").exists()
+                || non_empty_var("container").is_some(),
+        }
+    }
+}
+
+/// Apply the `[profiles.<name>]` tables which match the environment on top of the
+/// rest of the configuration. A profile selected with `STARSHIP_PROFILE` is
+/// applied alone, and otherwise every profile whose `when` rules all match is
+/// applied, in alphabetical order.
+pub fn apply_profiles(config: &mut Table, environment: &ProfileEnvironment) {
+    let profiles = match config.get("profiles").and_then(Value::as_table) {
+        Some(profiles) => profiles.clone(),
+        None => {
+            if let Some(selected) = &environment.selected {
+                log::warn!(
+                    "STARSHIP_PROFILE is set to \"{}\", but there are no profiles",
+                    selected
+                );
+            }
+            return;
+        }
+    };
+
+    let applied = match &environment.selected {
+        Some(selected) => {
+            if !profiles.contains_key(selected) {
+                log::warn!("STARSHIP_PROFILE is set to the unknown profile \"{}\"", selected);
+            }
+            profiles
+                .into_iter()
+                .filter(|(name, _)| name == selected)
+                .collect::<Vec<_>>()
+        }
+        None => profiles
+            .into_iter()
+            .filter(
+                |(_, profile)| matches!(profile.get("when"), Some(when) if profile_matches(when, environment)),
+            )
+            .collect(),
+    };
+
+    for (name, profile) in applied {
+        log::debug!("Applying profile \"{}\"", name);
+        if let Value::Table(mut profile) = profile {
+            profile.remove("when");
+            merge_tables(config, profile);
+        }
+    }
+}
+
+/// Whether all the `when` rules of a profile match the environment
+fn profile_matches(when: &Value, environment: &ProfileEnvironment) -> bool {
+    let rules = match when.as_table() {
+        Some(rules) => rules,
+        None => return false,
+    };
+
+    rules
+        .iter()
+        .all(|(rule, value)| match (rule.as_str(), value) {
+            ("hostname", Value::String(pattern)) => {
+                utils::glob_match(pattern, &environment.hostname)
+            }
+            ("ssh", Value::Boolean(is_ssh)) => *is_ssh == environment.is_ssh,
+            ("container", Value::Boolean(is_container)) => {
+                *is_container == environment.is_container
+            }
+            _ => {
+                log::warn!("Invalid profile rule {} = {}", rule, value);
+                false
+            }
+        })
+}
+
+/// Merge the configuration of a layer into the configuration of the layers below.
+/// Custom modules are ignored in per-directory files, as they run commands, and
+/// per-directory files come with any repository which is cloned.
+pub fn merge_layer(config: &mut Table, layer: ConfigLayer, mut layer_config: Table) {
+    if layer == ConfigLayer::Directory && remove_custom_modules(&mut layer_config) {
+        log::warn!(
+            "Ignoring custom modules in a per-directory {}",
+            DIRECTORY_CONFIG_FILE
+        );
+    }
+    merge_tables(config, layer_config);
+}
+
+/// Remove the custom modules of a configuration, including those of its profiles.
+/// Returns whether there were any.
+pub fn remove_custom_modules(config: &mut Table) -> bool {
+    let mut has_custom_modules = config.remove("custom").is_some();
+
+    if let Some(Value::Table(profiles)) = config.get_mut("profiles") {
+        for (_, profile) in profiles.iter_mut() {
+            if let Value::Table(profile) = profile {
+                has_custom_modules |= profile.remove("custom").is_some();
+            }
+        }
+    }
+    has_custom_modules
+}
+
+/// Deep-merge a table into another, table by table. Any other value replaces the
+/// value below it, including arrays.
+fn merge_tables(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => {
+                merge_tables(base_table, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
     }
 }
 
@@ -173,78 +514,200 @@ fn log_if_type_correct<T: std::fmt::Debug>(
  string include the following:
  - 'fg:<color>'    (specifies that the color read should be a foreground color)
  - 'bg:<color>'    (specifies that the color read should be a background color)
- - 'underline'
  - 'bold'
  - 'italic'
+ - 'underline'
+ - 'dimmed'
+ - 'blink'
+ - 'inverted'
+ - 'hidden'
+ - 'strikethrough'
+ - 'none'          (overrides all other tokens, leaving the style plain)
  - '<color>'        (see the parse_color_string doc for valid color strings)
+
+ Returns a description of the first invalid token, if any.
 */
-fn parse_style_string(style_string: &str) -> Option<ansi_term::Style> {
-    style_string
-        .split_whitespace()
-        .fold(Some(ansi_term::Style::new()), |maybe_style, token| {
-            maybe_style.and_then(|style| {
-                let token = token.to_lowercase();
-
-                // Check for FG/BG identifiers and strip them off if appropriate
-                // If col_fg is true, color the foreground. If it's false, color the background.
-                let (token, col_fg) = if token.as_str().starts_with("fg:") {
-                    (token.trim_start_matches("fg:").to_owned(), true)
-                } else if token.as_str().starts_with("bg:") {
-                    (token.trim_start_matches("bg:").to_owned(), false)
+pub fn parse_style_string(style_string: &str) -> Result<ansi_term::Style, String> {
+    let mut style = ansi_term::Style::new();
+    let mut is_none = false;
+
+    for token in style_tokens(style_string) {
+        let token = token.to_lowercase();
+
+        // Check for FG/BG identifiers and strip them off if appropriate
+        // If col_fg is true, color the foreground. If it's false, color the background.
+        let (token, col_fg) = if token.starts_with("fg:") {
+            (token.trim_start_matches("fg:"), true)
+        } else if token.starts_with("bg:") {
+            (token.trim_start_matches("bg:"), false)
+        } else {
+            (token.as_str(), true) // Bare colors are assumed to color the foreground
+        };
+
+        match token {
+            "bold" => style.is_bold = true,
+            "italic" => style.is_italic = true,
+            "underline" => style.is_underline = true,
+            "dimmed" => style.is_dimmed = true,
+            "blink" => style.is_blink = true,
+            "inverted" => style.is_reverse = true,
+            "hidden" => style.is_hidden = true,
+            "strikethrough" => style.is_strikethrough = true,
+            "none" => is_none = true,
+
+            // Try to see if this token parses as a valid color string
+            color_string => {
+                let color = parse_color_string(color_string)?;
+                if col_fg {
+                    style.foreground = Some(color);
                 } else {
-                    (token, true) // Bare colors are assumed to color the foreground
-                };
-
-                match token.as_str() {
-                    "underline" => Some(style.underline()),
-                    "bold" => Some(style.bold()),
-                    "italic" => Some(style.italic()),
-                    "dimmed" => Some(style.dimmed()),
-                    "none" => None,
-
-                    // Try to see if this token parses as a valid color string
-                    color_string => parse_color_string(color_string).map(|ansi_color| {
-                        if col_fg {
-                            style.fg(ansi_color)
-                        } else {
-                            style.on(ansi_color)
-                        }
-                    }),
+                    style.background = Some(color);
                 }
-            })
-        })
+            }
+        }
+    }
+
+    if is_none {
+        return Ok(ansi_term::Style::new());
+    }
+    Ok(style)
+}
+
+/// Split a style string into its tokens at whitespace, keeping the whitespace
+/// within parentheses, e.g. in `fg:rgb(0, 128, 255)`
+fn style_tokens(style_string: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    let mut depth = 0;
+
+    for (index, c) in style_string.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = token_start.take() {
+                    tokens.push(&style_string[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        token_start.get_or_insert(index);
+    }
+
+    if let Some(start) = token_start {
+        tokens.push(&style_string[start..]);
+    }
+    tokens
+}
+
+/// Make the colors of the palette selected in the configuration available to
+/// style strings
+pub fn load_palette(config: &Table) {
+    if PALETTE.set(get_palette(config)).is_err() {
+        log::debug!("The palette has already been loaded");
+    }
 }
 
-/** Parse a string that represents a color setting, returning None if this fails
- There are three valid color formats:
+/// Read the colors of the palette selected with `palette` from `[palettes]`.
+/// Colors which can't be parsed are left out.
+fn get_palette(config: &Table) -> HashMap<String, Color> {
+    let mut colors = HashMap::new();
+
+    let name = match config.get_as_str("palette") {
+        Some(name) => name,
+        None => return colors,
+    };
+    let palette = match config
+        .get_module_config("palettes")
+        .and_then(|palettes| palettes.get_module_config(name))
+    {
+        Some(palette) => palette,
+        None => {
+            log::warn!("Unable to find the palette \"{}\" in [palettes]", name);
+            return colors;
+        }
+    };
+
+    for (color_name, value) in palette {
+        let color = match value.as_str() {
+            Some(color) => parse_color_string(color),
+            None => Err(format!("expected a string, found {}", value.type_str())),
+        };
+        match color {
+            Ok(color) => {
+                colors.insert(color_name.to_lowercase(), color);
+            }
+            Err(error) => log::warn!(
+                "Ignoring the color of \"{}\" in [palettes.{}]: {}",
+                color_name,
+                name,
+                error
+            ),
+        }
+    }
+
+    colors
+}
+
+/** Parse a string that represents a color setting, describing the problem if
+ this fails. The valid color formats are:
   - #RRGGBB      (a hash followed by an RGB hex)
+  - #RGB         (a hash followed by a short RGB hex, e.g. #f80 for #ff8800)
+  - rgb(r,g,b)   (three numbers from 0-255)
   - u8           (a number from 0-255, representing an ANSI color)
   - colstring    (one of the 16 predefined color strings)
+  - CSS name     (one of the named colors of CSS, e.g. rebeccapurple)
+
+ Colors of the active palette can also be referred to by name, and take
+ precedence over the predefined color strings.
 */
-fn parse_color_string(color_string: &str) -> Option<ansi_term::Color> {
-    // Parse RGB hex values
+pub fn parse_color_string(color_string: &str) -> Result<ansi_term::Color, String> {
     log::trace!("Parsing color_string: {}", color_string);
-    if color_string.starts_with('#') {
-        log::trace!(
-            "Attempting to read hexadecimal color string: {}",
-            color_string
-        );
-        let r: u8 = u8::from_str_radix(&color_string[1..3], 16).ok()?;
-        let g: u8 = u8::from_str_radix(&color_string[3..5], 16).ok()?;
-        let b: u8 = u8::from_str_radix(&color_string[5..7], 16).ok()?;
-        log::trace!("Read RGB color string: {},{},{}", r, g, b);
-        return Some(Color::RGB(r, g, b));
+    let color_string = color_string.to_lowercase();
+
+    // Parse RGB hex values
+    if let Some(hex) = color_string.strip_prefix('#') {
+        log::trace!("Attempting to read hexadecimal color string: {}", hex);
+        return parse_hex_color(hex)
+            .ok_or_else(|| format!("invalid hex color \"#{}\", expected #rrggbb or #rgb", hex));
+    }
+
+    // Parse rgb(r, g, b) values
+    if let Some(values) = color_string
+        .strip_prefix("rgb(")
+        .and_then(|values| values.strip_suffix(')'))
+    {
+        log::trace!("Attempting to read rgb color string: {}", values);
+        return parse_rgb_color(values).ok_or_else(|| {
+            format!(
+                "invalid rgb color \"{}\", expected three numbers from 0 to 255",
+                color_string
+            )
+        });
     }
 
     // Parse a u8 (ansi color)
-    if let Result::Ok(ansi_color_num) = color_string.parse::<u8>() {
-        log::trace!("Read ANSI color string: {}", ansi_color_num);
-        return Some(Color::Fixed(ansi_color_num));
+    if color_string.chars().all(|c| c.is_ascii_digit()) && !color_string.is_empty() {
+        log::trace!("Attempting to read ANSI color string: {}", color_string);
+        return color_string.parse::<u8>().map(Color::Fixed).map_err(|_| {
+            format!(
+                "invalid color number \"{}\", expected a number from 0 to 255",
+                color_string
+            )
+        });
+    }
+
+    // Check for a color of the active palette
+    let palette_color = PALETTE.get().and_then(|palette| palette.get(&color_string));
+    if let Some(color) = palette_color {
+        log::trace!("Read palette color string: {}", color_string);
+        return Ok(*color);
     }
 
     // Check for any predefined color strings
     // There are no predefined enums for bright colors, so we use Color::Fixed
-    let predefined_color = match color_string.to_lowercase().as_str() {
+    let predefined_color = match color_string.as_str() {
         "black" => Some(Color::Black),
         "red" => Some(Color::Red),
         "green" => Some(Color::Green),
@@ -261,15 +724,105 @@ fn parse_color_string(color_string: &str) -> Option<ansi_term::Color> {
         "bright-purple" => Some(Color::Fixed(13)),
         "bright-cyan" => Some(Color::Fixed(14)),
         "bright-white" => Some(Color::Fixed(15)),
-        _ => None,
+        css_name => colors::css_color(css_name),
     };
 
-    if predefined_color.is_some() {
-        log::trace!("Read predefined color: {}", color_string);
-    } else {
-        log::debug!("Could not parse color in string: {}", color_string);
+    match predefined_color {
+        Some(color) => {
+            log::trace!("Read predefined color: {}", color_string);
+            Ok(color)
+        }
+        None => Err(format!("unknown color \"{}\"", color_string)),
+    }
+}
+
+/// Parse the digits of a `#rrggbb` or `#rgb` color
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let component = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some(Color::RGB(
+            component(&hex[0..2])?,
+            component(&hex[2..4])?,
+            component(&hex[4..6])?,
+        )),
+        // Each digit is repeated, so that e.g. `f` is read as `ff`
+        3 => Some(Color::RGB(
+            component(&hex[0..1])? * 17,
+            component(&hex[1..2])? * 17,
+            component(&hex[2..3])? * 17,
+        )),
+        _ => None,
+    }
+}
+
+/// Parse the `r, g, b` values of an `rgb(r, g, b)` color
+fn parse_rgb_color(values: &str) -> Option<Color> {
+    let values = values
+        .split(',')
+        .map(|value| value.trim().parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    match values.as_slice() {
+        [r, g, b] => Some(Color::RGB(*r, *g, *b)),
+        _ => None,
+    }
+}
+
+/// Write a style back into a style string which `parse_style_string` understands
+pub fn style_to_string(style: &ansi_term::Style) -> String {
+    let mut tokens = Vec::new();
+
+    if style.is_bold {
+        tokens.push("bold".to_string());
+    }
+    if style.is_italic {
+        tokens.push("italic".to_string());
+    }
+    if style.is_underline {
+        tokens.push("underline".to_string());
+    }
+    if style.is_dimmed {
+        tokens.push("dimmed".to_string());
+    }
+    if style.is_blink {
+        tokens.push("blink".to_string());
+    }
+    if style.is_reverse {
+        tokens.push("inverted".to_string());
+    }
+    if style.is_hidden {
+        tokens.push("hidden".to_string());
+    }
+    if style.is_strikethrough {
+        tokens.push("strikethrough".to_string());
+    }
+    if let Some(color) = style.foreground {
+        tokens.push(format!("fg:{}", color_to_string(color)));
+    }
+    if let Some(color) = style.background {
+        tokens.push(format!("bg:{}", color_to_string(color)));
+    }
+
+    tokens.join(" ")
+}
+
+fn color_to_string(color: Color) -> String {
+    match color {
+        Color::Black => "black".to_string(),
+        Color::Red => "red".to_string(),
+        Color::Green => "green".to_string(),
+        Color::Yellow => "yellow".to_string(),
+        Color::Blue => "blue".to_string(),
+        Color::Purple => "purple".to_string(),
+        Color::Cyan => "cyan".to_string(),
+        Color::White => "white".to_string(),
+        Color::Fixed(number) => number.to_string(),
+        Color::RGB(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
     }
-    predefined_color
 }
 
 #[cfg(test)]
@@ -341,19 +894,233 @@ mod tests {
     }
 
     #[test]
-    fn table_get_styles_bold_italic_underline_green_dimmy_silly_caps() {
+    fn table_get_nested_module_config() {
+        let mut foo = toml::value::Table::new();
+        foo.insert(String::from("command"), Value::String(String::from("echo")));
+        let mut custom = toml::value::Table::new();
+        custom.insert(String::from("foo"), Value::Table(foo.clone()));
         let mut table = toml::value::Table::new();
+        table.insert(String::from("custom"), Value::Table(custom));
 
-        table.insert(
-            String::from("mystyle"),
-            Value::String(String::from("bOlD ItAlIc uNdErLiNe GrEeN dimmed")),
+        assert_eq!(table.get_module_config("custom.foo"), Some(&foo));
+        assert_eq!(table.get_module_config("custom.bar"), None);
+        assert_eq!(table.get_module_config("other.foo"), None);
+    }
+
+    #[test]
+    fn palette_colors() {
+        let table: Table = toml::from_str(
+            r##"
+            palette = "mine"
+
+            [palettes.mine]
+            Mauve = "#c792ea"
+            red = "196"
+            broken = "not a color"
+
+            [palettes.other]
+            mauve = "purple"
+        "##,
+        )
+        .unwrap();
+
+        let palette = get_palette(&table);
+        assert_eq!(palette.get("mauve"), Some(&Color::RGB(0xc7, 0x92, 0xea)));
+        assert_eq!(palette.get("red"), Some(&Color::Fixed(196)));
+        assert_eq!(palette.get("broken"), None);
+
+        let mut table = table;
+        table.insert("palette".to_string(), Value::from("missing"));
+        assert!(get_palette(&table).is_empty());
+    }
+
+    #[test]
+    fn merge_layers() {
+        let mut config: Table = toml::from_str(
+            r#"
+            add_newline = false
+            prompt_order = ["directory", "character"]
+
+            [directory]
+            truncation_length = 2
+            style = "bold blue"
+        "#,
+        )
+        .unwrap();
+        let layer_config = toml::from_str(
+            r#"
+            prompt_order = ["character"]
+
+            [directory]
+            style = "red"
+
+            [package]
+            disabled = true
+
+            [custom.foo]
+            command = "echo foo"
+        "#,
+        )
+        .unwrap();
+        merge_layer(&mut config, ConfigLayer::Directory, layer_config);
+
+        let expected: Table = toml::from_str(
+            r#"
+            add_newline = false
+            prompt_order = ["character"]
+
+            [directory]
+            truncation_length = 2
+            style = "red"
+
+            [package]
+            disabled = true
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn include_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme_dir = dir.path().join("theme");
+        std::fs::create_dir(&theme_dir).unwrap();
+        std::fs::write(
+            dir.path().join("starship.toml"),
+            "include = [\"theme/theme.toml\", \"machine.toml\"]\n[jobs]\nsymbol = \"+\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            theme_dir.join("theme.toml"),
+            "include = [\"colors.toml\"]\n[jobs]\nstyle = \"red\"\n",
+        )
+        .unwrap();
+        std::fs::write(theme_dir.join("colors.toml"), "[palettes.night]\n").unwrap();
+        std::fs::write(dir.path().join("machine.toml"), "add_newline = false\n").unwrap();
+
+        let files = read_config_files(&dir.path().join("starship.toml")).unwrap();
+        let names = files
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["colors.toml", "theme.toml", "machine.toml", "starship.toml"]
         );
-        assert!(table.get_as_ansi_style("mystyle").unwrap().is_bold);
-        assert!(table.get_as_ansi_style("mystyle").unwrap().is_italic);
-        assert!(table.get_as_ansi_style("mystyle").unwrap().is_underline);
-        assert!(table.get_as_ansi_style("mystyle").unwrap().is_dimmed);
+
+        let config = Table::config_from_file(&dir.path().join("starship.toml")).unwrap();
+        let jobs = config.get_module_config("jobs").unwrap();
+        assert_eq!(jobs.get_as_str("symbol"), Some("+"));
+        assert_eq!(jobs.get_as_str("style"), Some("red"));
+        assert_eq!(config.get_as_bool("add_newline"), Some(false));
+        assert_eq!(config.get("include"), None);
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.toml"), "include = [\"b.toml\"]\n").unwrap();
+        std::fs::write(dir.path().join("b.toml"), "include = [\"a.toml\"]\n").unwrap();
+
+        match read_config_files(&dir.path().join("a.toml")) {
+            Err(ConfigError::Include(path, message)) => {
+                assert!(path.ends_with("b.toml"));
+                assert!(message.starts_with("include cycle: "));
+                assert!(message.ends_with("a.toml"));
+            }
+            other => panic!("expected an include cycle, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn profiles_matching_environment() {
+        let config = toml::toml! {
+            add_newline = true
+
+            [profiles.build]
+            when = { hostname = "build-*", ssh = true }
+            add_newline = false
+
+            [profiles.container]
+            when = { container = true }
+            [profiles.container.jobs]
+            symbol = "c"
+
+            [profiles.remote]
+            when = { ssh = true }
+            [profiles.remote.jobs]
+            symbol = "r"
+        };
+        let applied = |environment: &ProfileEnvironment| {
+            let mut config = config.as_table().unwrap().clone();
+            apply_profiles(&mut config, environment);
+            (
+                config.get_as_bool("add_newline"),
+                config
+                    .get_module_config("jobs")
+                    .and_then(|jobs| jobs.get_as_str("symbol"))
+                    .map(str::to_string),
+            )
+        };
+
+        let build_server = ProfileEnvironment {
+            hostname: String::from("build-04"),
+            is_ssh: true,
+            ..ProfileEnvironment::default()
+        };
+        assert_eq!(
+            applied(&build_server),
+            (Some(false), Some(String::from("r")))
+        );
+
+        let laptop = ProfileEnvironment {
+            hostname: String::from("laptop"),
+            is_ssh: true,
+            ..ProfileEnvironment::default()
+        };
+        assert_eq!(applied(&laptop), (Some(true), Some(String::from("r"))));
+
+        let container = ProfileEnvironment {
+            is_container: true,
+            ..ProfileEnvironment::default()
+        };
+        assert_eq!(applied(&container), (Some(true), Some(String::from("c"))));
+
+        let selected = ProfileEnvironment {
+            selected: Some(String::from("build")),
+            is_container: true,
+            ..ProfileEnvironment::default()
+        };
+        assert_eq!(applied(&selected), (Some(false), None));
+    }
+
+    #[test]
+    fn profile_with_invalid_rule_never_matches() {
+        let environment = ProfileEnvironment {
+            is_ssh: true,
+            ..ProfileEnvironment::default()
+        };
+        assert!(profile_matches(&toml::toml! { ssh = true }, &environment));
+        assert!(!profile_matches(&toml::toml! { ssh = "yes" }, &environment));
+        assert!(!profile_matches(
+            &toml::toml! {
+                ssh = true
+                os = "linux"
+            },
+            &environment
+        ));
+    }
+
+    #[test]
+    fn parse_styles_bold_italic_underline_green_dimmy_silly_caps() {
+        let style = parse_style_string("bOlD ItAlIc uNdErLiNe GrEeN dimmed").unwrap();
+        assert!(style.is_bold);
+        assert!(style.is_italic);
+        assert!(style.is_underline);
+        assert!(style.is_dimmed);
         assert_eq!(
-            table.get_as_ansi_style("mystyle").unwrap(),
+            style,
             ansi_term::Style::new()
                 .bold()
                 .italic()
@@ -364,71 +1131,108 @@ mod tests {
     }
 
     #[test]
-    fn table_get_styles_plain_and_broken_styles() {
-        let mut table = toml::value::Table::new();
+    fn parse_styles_plain_and_broken_styles() {
         // Test a "plain" style with no formatting
-        table.insert(String::from("plainstyle"), Value::String(String::from("")));
-        assert_eq!(
-            table.get_as_ansi_style("plainstyle").unwrap(),
-            ansi_term::Style::new()
-        );
+        assert_eq!(parse_style_string(""), Ok(ansi_term::Style::new()));
 
         // Test a string that's clearly broken
-        table.insert(
-            String::from("broken"),
-            Value::String(String::from("djklgfhjkldhlhk;j")),
-        );
-        assert_eq!(
-            table.get_as_ansi_style("broken").unwrap(),
-            ansi_term::Style::new()
-        );
+        assert!(parse_style_string("djklgfhjkldhlhk;j").is_err());
 
         // Test a string that's nullified by `none`
-        table.insert(
-            String::from("nullified"),
-            Value::String(String::from("fg:red bg:green bold none")),
-        );
         assert_eq!(
-            table.get_as_ansi_style("nullified").unwrap(),
-            ansi_term::Style::new()
+            parse_style_string("fg:red bg:green bold none"),
+            Ok(ansi_term::Style::new())
         );
 
         // Test a string that's nullified by `none` at the start
-        table.insert(
-            String::from("nullified-start"),
-            Value::String(String::from("none fg:red bg:green bold")),
-        );
         assert_eq!(
-            table.get_as_ansi_style("nullified-start").unwrap(),
-            ansi_term::Style::new()
+            parse_style_string("none fg:red bg:green bold"),
+            Ok(ansi_term::Style::new())
         );
     }
 
     #[test]
-    fn table_get_styles_ordered() {
-        let mut table = toml::value::Table::new();
-
+    fn parse_styles_ordered() {
         // Test a background style with inverted order (also test hex + ANSI)
-        table.insert(
-            String::from("flipstyle"),
-            Value::String(String::from("bg:#050505 underline fg:120")),
-        );
         assert_eq!(
-            table.get_as_ansi_style("flipstyle").unwrap(),
-            Style::new()
+            parse_style_string("bg:#050505 underline fg:120"),
+            Ok(Style::new()
                 .underline()
                 .fg(Color::Fixed(120))
-                .on(Color::RGB(5, 5, 5))
+                .on(Color::RGB(5, 5, 5)))
         );
 
         // Test that the last color style is always the one used
-        table.insert(
-            String::from("multistyle"),
-            Value::String(String::from("bg:120 bg:125 bg:127 fg:127 122 125")),
+        assert_eq!(
+            parse_style_string("bg:120 bg:125 bg:127 fg:127 122 125"),
+            Ok(Style::new().fg(Color::Fixed(125)).on(Color::Fixed(127)))
+        );
+    }
+
+    #[test]
+    fn styles_extra_attributes() {
+        assert_eq!(
+            parse_style_string("blink inverted hidden strikethrough"),
+            Ok(Style::new().blink().reverse().hidden().strikethrough())
+        );
+    }
+
+    #[test]
+    fn styles_color_formats() {
+        assert_eq!(
+            parse_style_string("fg:#F80 bg:rgb(0, 128, 255)"),
+            Ok(Style::new()
+                .fg(Color::RGB(255, 136, 0))
+                .on(Color::RGB(0, 128, 255)))
+        );
+        assert_eq!(
+            parse_style_string("RebeccaPurple bg:navy"),
+            Ok(Style::new()
+                .fg(Color::RGB(102, 51, 153))
+                .on(Color::RGB(0, 0, 128)))
         );
+        // The terminal's own colors take precedence over CSS colors of the same name
+        assert_eq!(parse_color_string("purple"), Ok(Color::Purple));
+    }
+
+    #[test]
+    fn styles_malformed_colors() {
+        assert_eq!(
+            parse_style_string("bold #12"),
+            Err(String::from(
+                "invalid hex color \"#12\", expected #rrggbb or #rgb"
+            ))
+        );
+        assert!(parse_color_string("#").is_err());
+        assert!(parse_color_string("#ééé").is_err());
+        assert!(parse_color_string("#12345g").is_err());
+        assert!(parse_color_string("rgb(1, 2)").is_err());
+        assert!(parse_color_string("rgb(1, 2, 256)").is_err());
+        assert_eq!(
+            parse_color_string("256"),
+            Err(String::from(
+                "invalid color number \"256\", expected a number from 0 to 255"
+            ))
+        );
+        assert_eq!(
+            parse_style_string("bold rde none"),
+            Err(String::from("unknown color \"rde\""))
+        );
+    }
+
+    #[test]
+    fn style_to_string_round_trip() {
+        let style = Style::new()
+            .bold()
+            .underline()
+            .strikethrough()
+            .fg(Color::Fixed(120))
+            .on(Color::RGB(5, 10, 255));
         assert_eq!(
-            table.get_as_ansi_style("multistyle").unwrap(),
-            Style::new().fg(Color::Fixed(125)).on(Color::Fixed(127))
+            style_to_string(&style),
+            "bold underline strikethrough fg:120 bg:#050aff"
         );
+        assert_eq!(parse_style_string(&style_to_string(&style)), Ok(style));
+        assert_eq!(style_to_string(&Style::new()), "");
     }
 }
@@ -0,0 +1,506 @@
+use toml::value::{Table, Value};
+
+/// A statement of a configuration file, and the lines it spans
+struct Statement {
+    kind: StatementKind,
+    first_line: usize,
+    last_line: usize,
+}
+
+enum StatementKind {
+    /// A `[table]` or `[[array]]` header
+    Header { path: Vec<String>, is_array: bool },
+    /// A `key = value` pair. The path of the option starts with the path of the
+    /// table it is in, whose length is `table_len`.
+    Entry {
+        path: Vec<String>,
+        table_len: usize,
+        in_array: bool,
+    },
+}
+
+/// A configuration file, split into lines so that only the lines of changed
+/// options are rewritten. Comments, blank lines and the formatting of other
+/// options are kept as they are.
+struct Document {
+    lines: Vec<String>,
+    statements: Vec<Statement>,
+    line_ending: &'static str,
+}
+
+impl Document {
+    fn parse(text: &str) -> Result<Document, String> {
+        toml::from_str::<Table>(text).map_err(|error| error.to_string())?;
+
+        let lines = text.lines().map(String::from).collect::<Vec<_>>();
+        let mut statements = Vec::new();
+        let mut table_path = Vec::new();
+        let mut in_array = false;
+        let mut line = 0;
+        while line < lines.len() {
+            let trimmed = lines[line].trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                line += 1;
+                continue;
+            }
+
+            if trimmed.starts_with('[') {
+                let header = toml::from_str::<Table>(trimmed).map_err(|error| error.to_string())?;
+                table_path = key_path(&header);
+                in_array = trimmed.starts_with("[[");
+                statements.push(Statement {
+                    kind: StatementKind::Header {
+                        path: table_path.clone(),
+                        is_array: in_array,
+                    },
+                    first_line: line,
+                    last_line: line,
+                });
+                line += 1;
+                continue;
+            }
+
+            // A value such as an array may span several lines, and ends with the
+            // first line at which the statement can be parsed
+            let mut last_line = line;
+            while toml::from_str::<Table>(&lines[line..=last_line].join("\n")).is_err() {
+                last_line += 1;
+                if last_line == lines.len() {
+                    return Err(format!("unable to parse line {}", line + 1));
+                }
+            }
+
+            let key = &lines[line][..find_equals(&lines[line])?];
+            let key = toml::from_str::<Table>(&format!("{} = 0", key))
+                .map_err(|error| error.to_string())?;
+            let mut path = table_path.clone();
+            path.extend(key_path(&key));
+            statements.push(Statement {
+                kind: StatementKind::Entry {
+                    path,
+                    table_len: table_path.len(),
+                    in_array,
+                },
+                first_line: line,
+                last_line,
+            });
+            line = last_line + 1;
+        }
+
+        Ok(Document {
+            lines,
+            statements,
+            line_ending: if text.contains("\r\n") { "\r\n" } else { "\n" },
+        })
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = self.lines.join(self.line_ending);
+        if !text.is_empty() {
+            text.push_str(self.line_ending);
+        }
+        text
+    }
+
+    /// The lines of the statement setting the option, unless it's within an
+    /// array of tables
+    fn find_entry(&self, path: &[String]) -> Option<(usize, usize)> {
+        self.statements
+            .iter()
+            .find(|statement| match &statement.kind {
+                StatementKind::Entry {
+                    path: entry_path,
+                    in_array: false,
+                    ..
+                } => entry_path.as_slice() == path,
+                _ => false,
+            })
+            .map(|entry| (entry.first_line, entry.last_line))
+    }
+
+    fn set(&mut self, path: &[String], value: &Value) {
+        let value = format_value(value);
+
+        if let Some((first_line, last_line)) = self.find_entry(path) {
+            let statement = self.lines[first_line..=last_line].join("\n");
+            let equals = find_equals(&statement).unwrap_or_default();
+            let value_start = statement.len() - statement[equals + 1..].trim_start().len();
+            let line = format!(
+                "{}{}{}",
+                &statement[..value_start],
+                value,
+                trailing_comment(&statement[value_start..])
+            );
+            self.lines.splice(first_line..=last_line, vec![line]);
+            return;
+        }
+
+        // The option is added after the last option of its table, using the key
+        // relative to the table that option is in, e.g. `jobs.symbol` at the root
+        let (table, key) = path.split_at(path.len() - 1);
+        let sibling = self
+            .statements
+            .iter()
+            .rev()
+            .find(|statement| match &statement.kind {
+                StatementKind::Entry {
+                    path: entry_path,
+                    in_array: false,
+                    ..
+                } => entry_path.len() == path.len() && entry_path[..table.len()] == *table,
+                _ => false,
+            });
+        if let Some(Statement {
+            kind: StatementKind::Entry { table_len, .. },
+            last_line,
+            ..
+        }) = sibling
+        {
+            let line = format!("{} = {}", format_key_path(&path[*table_len..]), value);
+            self.lines.insert(last_line + 1, line);
+            return;
+        }
+
+        let line = format!("{} = {}", format_key_path(key), value);
+        if table.is_empty() {
+            // Root options must come before the first table
+            if matches!(self.lines.first(), Some(line) if !line.trim().is_empty()) {
+                self.lines.insert(0, String::new());
+            }
+            self.lines.insert(0, line);
+            return;
+        }
+
+        let header = self
+            .statements
+            .iter()
+            .find(|statement| match &statement.kind {
+                StatementKind::Header {
+                    path: header_path,
+                    is_array: false,
+                } => header_path.as_slice() == table,
+                _ => false,
+            })
+            .map(|header| header.last_line);
+        match header {
+            Some(header_line) => self.lines.insert(header_line + 1, line),
+            None => {
+                if matches!(self.lines.last(), Some(line) if !line.trim().is_empty()) {
+                    self.lines.push(String::new());
+                }
+                self.lines.push(format!("[{}]", format_key_path(table)));
+                self.lines.push(line);
+            }
+        }
+    }
+
+    /// Remove an option, or a table along with the tables nested in it. Returns
+    /// whether anything was removed.
+    fn unset(&mut self, path: &[String]) -> bool {
+        if let Some((first_line, last_line)) = self.find_entry(path) {
+            self.lines.drain(first_line..=last_line);
+            return true;
+        }
+
+        // A table starts with the comments right above its header, and ends with
+        // its last option, so that the comments before the next table are kept
+        let mut tables = Vec::new();
+        for (index, statement) in self.statements.iter().enumerate() {
+            if let StatementKind::Header {
+                path: header_path,
+                is_array,
+            } = &statement.kind
+            {
+                if header_path.starts_with(path) && !(*is_array && header_path.len() == path.len())
+                {
+                    let last_line = self.statements[index + 1..]
+                        .iter()
+                        .take_while(|statement| match statement.kind {
+                            StatementKind::Entry { .. } => true,
+                            StatementKind::Header { .. } => false,
+                        })
+                        .last()
+                        .map_or(statement.last_line, |entry| entry.last_line);
+                    let earliest_line = match index {
+                        0 => 0,
+                        _ => self.statements[index - 1].last_line + 1,
+                    };
+                    let mut first_line = statement.first_line;
+                    while first_line > earliest_line
+                        && self.lines[first_line - 1].trim_start().starts_with('#')
+                    {
+                        first_line -= 1;
+                    }
+                    tables.push((first_line, last_line));
+                }
+            }
+        }
+
+        for &(first_line, last_line) in tables.iter().rev() {
+            self.lines.drain(first_line..=last_line);
+
+            // Don't leave a blank line behind at either end of the file, or next
+            // to another blank line
+            let lines = &self.lines;
+            let is_blank =
+                |index: usize| !matches!(lines.get(index), Some(line) if !line.trim().is_empty());
+            if first_line < lines.len()
+                && is_blank(first_line)
+                && (first_line == 0 || is_blank(first_line - 1))
+            {
+                self.lines.remove(first_line);
+            } else if first_line == lines.len() && first_line > 0 && is_blank(first_line - 1) {
+                self.lines.remove(first_line - 1);
+            }
+        }
+        !tables.is_empty()
+    }
+}
+
+/// Set an option of a configuration file, given as a dotted path such as
+/// `directory.truncation_length`. Returns the changed file.
+pub fn set_option(text: &str, key: &str, value: &Value) -> Result<String, String> {
+    let path = split_key(key)?;
+    let mut document = Document::parse(text)?;
+    document.set(&path, value);
+    let text = document.to_text();
+
+    // Options set with an inline table are better left to the user to change
+    match toml::from_str::<Table>(&text) {
+        Ok(ref config) if get_option(config, key) == Some(value) => Ok(text),
+        _ => Err(format!(
+            "unable to set \"{}\" without rewriting the file, edit it with `starship config edit`",
+            key
+        )),
+    }
+}
+
+/// Remove an option or a table of a configuration file. Returns the changed
+/// file, or `None` when the option isn't set.
+pub fn unset_option(text: &str, key: &str) -> Result<Option<String>, String> {
+    let path = split_key(key)?;
+    let mut document = Document::parse(text)?;
+    if !document.unset(&path) {
+        let config = toml::from_str::<Table>(text).map_err(|error| error.to_string())?;
+        return match get_option(&config, key) {
+            Some(_) => Err(format!(
+                "unable to unset \"{}\" without rewriting the file, edit it with `starship config edit`",
+                key
+            )),
+            None => Ok(None),
+        };
+    }
+
+    let text = document.to_text();
+    match toml::from_str::<Table>(&text) {
+        Ok(ref config) if get_option(config, key).is_none() => Ok(Some(text)),
+        _ => Err(format!(
+            "unable to unset \"{}\" without rewriting the file, edit it with `starship config edit`",
+            key
+        )),
+    }
+}
+
+/// Get an option given as a dotted path such as `git_branch.style`
+pub fn get_option<'a>(config: &'a Table, key: &str) -> Option<&'a Value> {
+    let mut segments = key.split('.');
+    let mut value = config.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
+
+/// Parse a value given on the command line. Anything which isn't a TOML value,
+/// such as `bold red`, is taken as a string.
+pub fn parse_value(text: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {}", text))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+/// Format a value as it's written after `key = `, with tables written inline
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::Array(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Table(table) if table.is_empty() => String::from("{}"),
+        Value::Table(table) => format!(
+            "{{ {} }}",
+            table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", format_key(key), format_value(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => value.to_string(),
+    }
+}
+
+fn split_key(key: &str) -> Result<Vec<String>, String> {
+    let path = key.split('.').map(String::from).collect::<Vec<_>>();
+    if path.iter().any(String::is_empty) {
+        return Err(format!("invalid option \"{}\"", key));
+    }
+    Ok(path)
+}
+
+/// The path of nested keys of a table parsed from a header or a key, e.g.
+/// `["git_branch", "style"]` for `git_branch.style = 0`
+fn key_path(table: &Table) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut table = table;
+    while table.len() == 1 {
+        let (key, value) = table.iter().next().unwrap();
+        path.push(key.clone());
+        match value {
+            Value::Table(nested) => table = nested,
+            _ => break,
+        }
+    }
+    path
+}
+
+/// The position of the `=` between a key and its value, skipping quoted keys
+fn find_equals(line: &str) -> Result<usize, String> {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '=') => return Ok(index),
+            _ => {}
+        }
+    }
+    Err(format!("expected \"=\" in \"{}\"", line))
+}
+
+/// The comment after a value, along with the whitespace before it. A `#` within
+/// a string is part of the value, which is only complete before the comment.
+fn trailing_comment(value: &str) -> &str {
+    let last_line_start = value.rfind('\n').map_or(0, |index| index + 1);
+    value[last_line_start..]
+        .match_indices('#')
+        .map(|(index, _)| last_line_start + index)
+        .find(|&index| toml::from_str::<Table>(&format!("value = {}", &value[..index])).is_ok())
+        .map_or("", |index| &value[value[..index].trim_end().len()..])
+}
+
+fn format_key_path(path: &[String]) -> String {
+    path.iter()
+        .map(|key| format_key(key))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn format_key(key: &str) -> String {
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_bare {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"# My prompt
+add_newline = false
+
+# Branches
+[git_branch]
+symbol = "🌱 "  # a seedling
+style = "bold purple"
+
+[directory]
+truncation_length = 3
+prompt_order = [
+    "directory", # the path
+    "character",
+]
+"#;
+
+    #[test]
+    fn set_existing_options() {
+        let text = set_option(CONFIG, "git_branch.symbol", &parse_value("#")).unwrap();
+        assert!(text.contains("\nsymbol = \"#\"  # a seedling\n"));
+
+        let text = set_option(CONFIG, "directory.prompt_order", &parse_value("[]")).unwrap();
+        assert!(text.ends_with("truncation_length = 3\nprompt_order = []\n"));
+
+        let text = set_option(CONFIG, "add_newline", &parse_value("true")).unwrap();
+        assert_eq!(
+            text,
+            CONFIG.replace("add_newline = false", "add_newline = true")
+        );
+    }
+
+    #[test]
+    fn set_new_options() {
+        let text = set_option(CONFIG, "git_branch.disabled", &parse_value("true")).unwrap();
+        assert!(text.contains("style = \"bold purple\"\ndisabled = true\n\n[directory]"));
+
+        let text = set_option(CONFIG, "palette", &parse_value("night")).unwrap();
+        assert!(text.starts_with("# My prompt\nadd_newline = false\npalette = \"night\"\n"));
+
+        let text = set_option(CONFIG, "custom.foo.command", &parse_value("echo foo")).unwrap();
+        assert!(text.ends_with("]\n\n[custom.foo]\ncommand = \"echo foo\"\n"));
+
+        let text = set_option("", "jobs.threshold", &parse_value("2")).unwrap();
+        assert_eq!(text, "[jobs]\nthreshold = 2\n");
+
+        let text = set_option("jobs.symbol = \"+\"\n", "jobs.threshold", &parse_value("2"));
+        assert_eq!(text.unwrap(), "jobs.symbol = \"+\"\njobs.threshold = 2\n");
+    }
+
+    #[test]
+    fn set_option_in_inline_table() {
+        let config = "[profiles.remote]\nwhen = { ssh = true }\n";
+        assert!(set_option(config, "profiles.remote.when.hostname", &parse_value("a")).is_err());
+    }
+
+    #[test]
+    fn unset_options_and_tables() {
+        let text = unset_option(CONFIG, "git_branch.symbol").unwrap().unwrap();
+        assert!(text.contains("[git_branch]\nstyle = \"bold purple\"\n"));
+
+        let text = unset_option(CONFIG, "directory.prompt_order")
+            .unwrap()
+            .unwrap();
+        assert!(text.ends_with("[directory]\ntruncation_length = 3\n"));
+
+        let text = unset_option(CONFIG, "directory").unwrap().unwrap();
+        assert!(text.ends_with("style = \"bold purple\"\n"));
+
+        let text = unset_option(CONFIG, "git_branch").unwrap().unwrap();
+        assert!(text.starts_with("# My prompt\nadd_newline = false\n\n[directory]\n"));
+
+        assert_eq!(unset_option(CONFIG, "jobs.symbol"), Ok(None));
+    }
+
+    #[test]
+    fn values_from_command_line() {
+        assert_eq!(parse_value("5"), Value::Integer(5));
+        assert_eq!(
+            parse_value("bold red"),
+            Value::String(String::from("bold red"))
+        );
+        assert_eq!(parse_value("\"5\""), Value::String(String::from("5")));
+        assert_eq!(
+            format_value(&parse_value("{ ssh = true, \"host name\" = [\"a\"] }")),
+            "{ \"host name\" = [\"a\"], ssh = true }"
+        );
+    }
+}
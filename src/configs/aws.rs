@@ -0,0 +1,26 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `aws` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AwsConfig {
+    pub symbol: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for AwsConfig {
+    fn default() -> Self {
+        AwsConfig {
+            symbol: "☁️ ".to_string(),
+            style: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for AwsConfig {}
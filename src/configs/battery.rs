@@ -0,0 +1,36 @@
+use ansi_term::Color;
+use serde::{Deserialize, Serialize};
+
+use super::{ModuleConfig, ThresholdStyle};
+
+/// The configuration of the `battery` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatteryConfig {
+    pub full_symbol: String,
+    pub charging_symbol: String,
+    pub discharging_symbol: String,
+    pub unknown_symbol: Option<String>,
+    pub empty_symbol: Option<String>,
+    pub display: Vec<ThresholdStyle>,
+    pub disabled: bool,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        BatteryConfig {
+            full_symbol: "•".to_string(),
+            charging_symbol: "⇡".to_string(),
+            discharging_symbol: "⇣".to_string(),
+            unknown_symbol: None,
+            empty_symbol: None,
+            display: vec![ThresholdStyle {
+                threshold: 10,
+                style: Color::Red.bold(),
+            }],
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for BatteryConfig {}
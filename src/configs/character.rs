@@ -0,0 +1,35 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `character` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CharacterConfig {
+    pub symbol: String,
+    pub error_symbol: String,
+    pub vicmd_symbol: String,
+    pub use_symbol_for_status: bool,
+    #[serde(with = "style_string")]
+    pub style_success: Style,
+    #[serde(with = "style_string")]
+    pub style_failure: Style,
+    pub disabled: bool,
+}
+
+impl Default for CharacterConfig {
+    fn default() -> Self {
+        CharacterConfig {
+            symbol: "❯".to_string(),
+            error_symbol: "✖".to_string(),
+            vicmd_symbol: "❮".to_string(),
+            use_symbol_for_status: false,
+            style_success: Color::Green.bold(),
+            style_failure: Color::Red.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for CharacterConfig {}
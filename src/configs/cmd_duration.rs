@@ -0,0 +1,28 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig, ThresholdStyle};
+
+/// The configuration of the `cmd_duration` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CmdDurationConfig {
+    pub min_time: i64,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub display: Vec<ThresholdStyle>,
+    pub disabled: bool,
+}
+
+impl Default for CmdDurationConfig {
+    fn default() -> Self {
+        CmdDurationConfig {
+            min_time: 2,
+            style: Color::Yellow.bold(),
+            display: Vec::new(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for CmdDurationConfig {}
@@ -0,0 +1,26 @@
+use ansi_term::Style;
+use serde::{Deserialize, Serialize};
+
+use super::{optional_style_string, ModuleConfig};
+
+/// The configuration of the prompt shown when a command spans multiple lines
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContinuationPromptConfig {
+    pub symbol: String,
+
+    /// Defaults to the success style of the `character` module
+    #[serde(with = "optional_style_string")]
+    pub style: Option<Style>,
+}
+
+impl Default for ContinuationPromptConfig {
+    fn default() -> Self {
+        ContinuationPromptConfig {
+            symbol: "∙".to_string(),
+            style: None,
+        }
+    }
+}
+
+impl ModuleConfig for ContinuationPromptConfig {}
@@ -0,0 +1,60 @@
+use ansi_term::{Color, Style};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of a `[custom.<name>]` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomConfig {
+    pub command: Option<String>,
+    pub when: Option<String>,
+    pub files: Vec<String>,
+    pub extensions: Vec<String>,
+    pub directories: Vec<String>,
+
+    /// The shell and its arguments. Accepts a single string, e.g. `"bash"`.
+    #[serde(deserialize_with = "string_or_array")]
+    pub shell: Vec<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for CustomConfig {
+    fn default() -> Self {
+        CustomConfig {
+            command: None,
+            when: None,
+            files: Vec::new(),
+            extensions: Vec::new(),
+            directories: Vec::new(),
+            shell: Vec::new(),
+            symbol: None,
+            description: None,
+            style: Color::Green.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for CustomConfig {}
+
+fn string_or_array<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrArray {
+        String(String),
+        Array(Vec<String>),
+    }
+
+    let shell = StringOrArray::deserialize(deserializer)
+        .map_err(|_| D::Error::custom("expected a string or an array of strings"))?;
+    match shell {
+        StringOrArray::String(string) => Ok(vec![string]),
+        StringOrArray::Array(array) => Ok(array),
+    }
+}
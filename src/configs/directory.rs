@@ -0,0 +1,32 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `directory` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DirectoryConfig {
+    pub truncation_length: i64,
+    pub truncate_to_repo: bool,
+    pub fish_style_pwd_dir_length: i64,
+    pub use_logical_path: bool,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for DirectoryConfig {
+    fn default() -> Self {
+        DirectoryConfig {
+            truncation_length: 3,
+            truncate_to_repo: true,
+            fish_style_pwd_dir_length: 0,
+            use_logical_path: true,
+            style: Color::Cyan.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for DirectoryConfig {}
@@ -0,0 +1,34 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `env_var` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvVarConfig {
+    pub symbol: Option<String>,
+    pub variable: Option<String>,
+    pub default: Option<String>,
+    pub prefix: String,
+    pub suffix: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for EnvVarConfig {
+    fn default() -> Self {
+        EnvVarConfig {
+            symbol: None,
+            variable: None,
+            default: None,
+            prefix: String::new(),
+            suffix: String::new(),
+            style: Color::Black.bold().dimmed(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for EnvVarConfig {}
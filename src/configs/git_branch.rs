@@ -0,0 +1,30 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `git_branch` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitBranchConfig {
+    pub symbol: String,
+    pub truncation_length: i64,
+    pub truncation_symbol: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for GitBranchConfig {
+    fn default() -> Self {
+        GitBranchConfig {
+            symbol: " ".to_string(),
+            truncation_length: i64::MAX,
+            truncation_symbol: "…".to_string(),
+            style: Color::Purple.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for GitBranchConfig {}
@@ -0,0 +1,40 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `git_state` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitStateConfig {
+    pub merge: String,
+    pub revert: String,
+    pub cherry_pick: String,
+    pub bisect: String,
+    pub am: String,
+    pub rebase: String,
+    pub am_or_rebase: String,
+    pub progress_divider: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for GitStateConfig {
+    fn default() -> Self {
+        GitStateConfig {
+            merge: "MERGING".to_string(),
+            revert: "REVERTING".to_string(),
+            cherry_pick: "CHERRY-PICKING".to_string(),
+            bisect: "BISECTING".to_string(),
+            am: "AM".to_string(),
+            rebase: "REBASING".to_string(),
+            am_or_rebase: "AM/REBASE".to_string(),
+            progress_divider: "/".to_string(),
+            style: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for GitStateConfig {}
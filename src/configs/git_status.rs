@@ -0,0 +1,52 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig, ThresholdStyle};
+
+/// The configuration of the `git_status` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitStatusConfig {
+    pub conflicted: String,
+    pub ahead: String,
+    pub behind: String,
+    pub diverged: String,
+    pub untracked: String,
+    pub stashed: String,
+    pub modified: String,
+    pub staged: String,
+    pub renamed: String,
+    pub deleted: String,
+    pub show_sync_count: bool,
+    pub prefix: String,
+    pub suffix: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub display: Vec<ThresholdStyle>,
+    pub disabled: bool,
+}
+
+impl Default for GitStatusConfig {
+    fn default() -> Self {
+        GitStatusConfig {
+            conflicted: "=".to_string(),
+            ahead: "⇡".to_string(),
+            behind: "⇣".to_string(),
+            diverged: "⇕".to_string(),
+            untracked: "?".to_string(),
+            stashed: "$".to_string(),
+            modified: "!".to_string(),
+            staged: "+".to_string(),
+            renamed: "»".to_string(),
+            deleted: "✘".to_string(),
+            show_sync_count: false,
+            prefix: "[".to_string(),
+            suffix: "] ".to_string(),
+            style: Color::Red.bold(),
+            display: Vec::new(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for GitStatusConfig {}
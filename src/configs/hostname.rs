@@ -0,0 +1,30 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `hostname` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostnameConfig {
+    pub ssh_only: bool,
+    pub prefix: String,
+    pub suffix: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for HostnameConfig {
+    fn default() -> Self {
+        HostnameConfig {
+            ssh_only: true,
+            prefix: String::new(),
+            suffix: String::new(),
+            style: Color::Green.bold().dimmed(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for HostnameConfig {}
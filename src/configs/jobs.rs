@@ -0,0 +1,30 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig, ThresholdStyle};
+
+/// The configuration of the `jobs` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    pub symbol: String,
+    pub threshold: i64,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub display: Vec<ThresholdStyle>,
+    pub disabled: bool,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        JobsConfig {
+            symbol: "✦".to_string(),
+            threshold: 1,
+            style: Color::Blue.bold(),
+            display: Vec::new(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for JobsConfig {}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use super::ModuleConfig;
+
+/// The configuration of the `line_break` module
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LineBreakConfig {
+    pub disabled: bool,
+}
+
+impl ModuleConfig for LineBreakConfig {}
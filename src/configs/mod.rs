@@ -0,0 +1,495 @@
+use ansi_term::Style;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use toml::value::{Table, Value};
+
+pub mod aws;
+pub mod battery;
+pub mod character;
+pub mod cmd_duration;
+pub mod continuation_prompt;
+pub mod custom;
+pub mod directory;
+pub mod env_var;
+pub mod fill;
+pub mod git_branch;
+pub mod git_state;
+pub mod git_status;
+pub mod golang;
+pub mod hostname;
+pub mod java;
+pub mod jobs;
+pub mod line_break;
+pub mod nix_shell;
+pub mod nodejs;
+pub mod package;
+pub mod powerline;
+pub mod python;
+pub mod ruby;
+pub mod rust;
+pub mod time;
+pub mod username;
+
+/// The typed configuration of a module, deserialized from the module's table.
+/// Every option has a default, which is used when the option isn't set.
+pub trait ModuleConfig: Default + DeserializeOwned + Serialize {
+    /// Load the configuration from the table of the named module. Options which
+    /// can't be loaded are logged and left at their default.
+    fn load(name: &str, config: Option<&Table>) -> Self {
+        let config = match config {
+            Some(config) => without_segment_overrides(name, config),
+            None => return Self::default(),
+        };
+
+        if let Ok(loaded) = Self::try_load(&config) {
+            return loaded;
+        }
+
+        let mut valid_options = Table::new();
+        for (key, value) in &config {
+            match check_option::<Self>(key, value) {
+                None => {
+                    valid_options.insert(key.clone(), value.clone());
+                }
+                Some(error) => log::warn!("Ignoring \"{}\" in [{}]: {}", key, name, error),
+            }
+        }
+        Self::try_load(&valid_options).unwrap_or_default()
+    }
+
+    /// Load the configuration from the module's table, failing on the first
+    /// option which can't be loaded
+    fn try_load(config: &Table) -> Result<Self, toml::de::Error> {
+        let mut config = config.clone();
+        // The format is understood by every module, and is handled by `Module` itself
+        config.remove("format");
+        Value::Table(config).try_into()
+    }
+
+    /// The default configuration, as it would be written in the module's table
+    fn default_table() -> Table {
+        match Value::try_from(Self::default()) {
+            Ok(Value::Table(table)) => table,
+            _ => Table::new(),
+        }
+    }
+
+    /// Lists the options of the module's table which can't be loaded
+    fn check(config: &Table) -> Vec<(String, OptionError)> {
+        config
+            .iter()
+            .filter(|(key, _)| *key != "format")
+            .filter_map(|(key, value)| Some((key.clone(), check_option::<Self>(key, value)?)))
+            .collect()
+    }
+}
+
+/// The reason an option of a module's table can't be loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionError {
+    /// The module has no option of this name
+    Unknown,
+
+    /// The value of the option is invalid, e.g. it has the wrong type
+    Invalid(String),
+}
+
+impl std::fmt::Display for OptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OptionError::Unknown => write!(f, "unknown option"),
+            OptionError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Check a single option by loading a table containing only that option
+fn check_option<T: ModuleConfig>(key: &str, value: &Value) -> Option<OptionError> {
+    let mut config = Table::new();
+    config.insert(key.to_string(), value.clone());
+
+    let error = T::try_load(&config).err()?.to_string();
+    if error.starts_with("unknown field") {
+        return Some(OptionError::Unknown);
+    }
+
+    // Errors end with the key they occurred at, which is already known
+    let message = match error.rfind(" for key `") {
+        Some(index) => &error[..index],
+        None => &error,
+    };
+    Some(OptionError::Invalid(message.to_string()))
+}
+
+/// The segments of a module which aren't options of the module, but whose value
+/// can still be set from the module's table as a string, see `Module::new_segment`.
+/// Custom modules are named by their table, e.g. `custom.foo`.
+pub fn segment_overrides(module: &str) -> &'static [&'static str] {
+    match module {
+        "aws" => &["profile"],
+        "battery" => &["percentage"],
+        "cmd_duration" => &["cmd_duration"],
+        "directory" => &["path"],
+        "env_var" => &["env_var"],
+        "git_branch" => &["name"],
+        "git_state" => &["progress_current", "progress_total"],
+        "git_status" => &["ahead_count", "behind_count"],
+        "golang" | "java" | "nodejs" | "package" | "ruby" | "rust" => &["version"],
+        "hostname" => &["hostname"],
+        "jobs" => &["number"],
+        "line_break" => &["character"],
+        "nix_shell" => &["nix_shell"],
+        "python" => &["version", "virtualenv"],
+        "time" => &["time"],
+        "username" => &["username"],
+        module if module.starts_with("custom.") => &["output"],
+        _ => &[],
+    }
+}
+
+/// Whether the value of a module's segment can be set from the module's table,
+/// either as one of the module's options or as a segment override
+pub fn is_settable_segment(module: &str, segment: &str) -> bool {
+    if segment_overrides(module).contains(&segment) {
+        return true;
+    }
+
+    let mut config = Table::new();
+    config.insert(segment.to_string(), Value::String(String::new()));
+    match check_module_config(module, &config) {
+        Some(problems) => problems
+            .iter()
+            .all(|(_, error)| *error != OptionError::Unknown),
+        None => true,
+    }
+}
+
+/// The module's table without its segment overrides, which are applied by
+/// `Module` itself rather than loaded into the module's configuration
+fn without_segment_overrides(module: &str, config: &Table) -> Table {
+    let mut config = config.clone();
+    for segment in segment_overrides(module) {
+        if let Some(Value::String(_)) = config.get(*segment) {
+            config.remove(*segment);
+        }
+    }
+    config
+}
+
+/// Check the table of the named module. Returns `None` if there is no such module.
+/// Custom modules are named by their table, e.g. `custom.foo`.
+pub fn check_module_config(module: &str, config: &Table) -> Option<Vec<(String, OptionError)>> {
+    let config = &without_segment_overrides(module, config);
+    let problems = match module {
+        "aws" => aws::AwsConfig::check(config),
+        "battery" => battery::BatteryConfig::check(config),
+        "character" => character::CharacterConfig::check(config),
+        "cmd_duration" => cmd_duration::CmdDurationConfig::check(config),
+        "continuation_prompt" => continuation_prompt::ContinuationPromptConfig::check(config),
+        "directory" => directory::DirectoryConfig::check(config),
+        "env_var" => env_var::EnvVarConfig::check(config),
+        "fill" => fill::FillConfig::check(config),
+        "git_branch" => git_branch::GitBranchConfig::check(config),
+        "git_state" => git_state::GitStateConfig::check(config),
+        "git_status" => git_status::GitStatusConfig::check(config),
+        "golang" => golang::GoConfig::check(config),
+        "hostname" => hostname::HostnameConfig::check(config),
+        "java" => java::JavaConfig::check(config),
+        "jobs" => jobs::JobsConfig::check(config),
+        "line_break" => line_break::LineBreakConfig::check(config),
+        "nix_shell" => nix_shell::NixShellConfig::check(config),
+        "nodejs" => nodejs::NodejsConfig::check(config),
+        "package" => package::PackageConfig::check(config),
+        "powerline" => powerline::PowerlineConfig::check(config),
+        "python" => python::PythonConfig::check(config),
+        "ruby" => ruby::RubyConfig::check(config),
+        "rust" => rust::RustConfig::check(config),
+        "time" => time::TimeConfig::check(config),
+        "username" => username::UsernameConfig::check(config),
+        module if module.starts_with("custom.") => custom::CustomConfig::check(config),
+        _ => return None,
+    };
+
+    // Segment overrides which aren't strings are left in the table, and reported
+    // as unknown options by the module's configuration
+    let overrides = segment_overrides(module);
+    let problems = problems
+        .into_iter()
+        .map(|(key, error)| match error {
+            OptionError::Unknown if overrides.contains(&key.as_str()) => {
+                (key, OptionError::Invalid(String::from("expected a string")))
+            }
+            error => (key, error),
+        })
+        .collect();
+    Some(problems)
+}
+
+/// The default configuration of the named module. Returns `None` if there is no
+/// such module. Custom modules are named by their table, e.g. `custom.foo`.
+pub fn default_module_config(module: &str) -> Option<Table> {
+    let defaults = match module {
+        "aws" => aws::AwsConfig::default_table(),
+        "battery" => battery::BatteryConfig::default_table(),
+        "character" => character::CharacterConfig::default_table(),
+        "cmd_duration" => cmd_duration::CmdDurationConfig::default_table(),
+        "continuation_prompt" => continuation_prompt::ContinuationPromptConfig::default_table(),
+        "directory" => directory::DirectoryConfig::default_table(),
+        "env_var" => env_var::EnvVarConfig::default_table(),
+        "fill" => fill::FillConfig::default_table(),
+        "git_branch" => git_branch::GitBranchConfig::default_table(),
+        "git_state" => git_state::GitStateConfig::default_table(),
+        "git_status" => git_status::GitStatusConfig::default_table(),
+        "golang" => golang::GoConfig::default_table(),
+        "hostname" => hostname::HostnameConfig::default_table(),
+        "java" => java::JavaConfig::default_table(),
+        "jobs" => jobs::JobsConfig::default_table(),
+        "line_break" => line_break::LineBreakConfig::default_table(),
+        "nix_shell" => nix_shell::NixShellConfig::default_table(),
+        "nodejs" => nodejs::NodejsConfig::default_table(),
+        "package" => package::PackageConfig::default_table(),
+        "powerline" => powerline::PowerlineConfig::default_table(),
+        "python" => python::PythonConfig::default_table(),
+        "ruby" => ruby::RubyConfig::default_table(),
+        "rust" => rust::RustConfig::default_table(),
+        "time" => time::TimeConfig::default_table(),
+        "username" => username::UsernameConfig::default_table(),
+        module if module.starts_with("custom.") => custom::CustomConfig::default_table(),
+        _ => return None,
+    };
+
+    Some(defaults)
+}
+
+/// A style used once a numeric value of a module crosses `threshold`, as set in
+/// the `display` array of e.g. `battery` or `cmd_duration`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdStyle {
+    pub threshold: i64,
+    #[serde(with = "style_string")]
+    pub style: Style,
+}
+
+/// The style of the highest threshold which the value is at or above, for values
+/// which are worse the higher they are
+pub fn style_at_or_above(display: &[ThresholdStyle], value: f64) -> Option<Style> {
+    display
+        .iter()
+        .filter(|display| value >= display.threshold as f64)
+        .max_by_key(|display| display.threshold)
+        .map(|display| display.style)
+}
+
+/// The style of the lowest threshold which the value is at or below, for values
+/// which are worse the lower they are
+#[cfg(any(feature = "battery", test))]
+pub fn style_at_or_below(display: &[ThresholdStyle], value: f64) -> Option<Style> {
+    display
+        .iter()
+        .filter(|display| value <= display.threshold as f64)
+        .min_by_key(|display| display.threshold)
+        .map(|display| display.style)
+}
+
+/// (De)serializes a style from a style string, e.g. `"bold red"`
+pub mod style_string {
+    use ansi_term::Style;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::config::{parse_style_string, style_to_string};
+
+    pub fn serialize<S: Serializer>(style: &Style, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&style_to_string(style))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Style, D::Error> {
+        let style_string = String::deserialize(deserializer)?;
+
+        parse_style_string(&style_string).map_err(|error| {
+            D::Error::custom(format!("invalid style \"{}\": {}", style_string, error))
+        })
+    }
+}
+
+/// (De)serializes an optional style, which is left unset when missing
+pub mod optional_style_string {
+    use ansi_term::Style;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        style: &Option<Style>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match style {
+            Some(style) => super::style_string::serialize(style, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Style>, D::Error> {
+        super::style_string::deserialize(deserializer).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::directory::DirectoryConfig;
+    use super::*;
+    use ansi_term::Color;
+
+    fn table(config: &str) -> Table {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn load_defaults() {
+        let config = DirectoryConfig::load("directory", None);
+        assert_eq!(config.truncation_length, 3);
+        assert_eq!(config.style, Color::Cyan.bold());
+    }
+
+    #[test]
+    fn segment_overrides() {
+        let table = table(
+            r#"
+            path = "~"
+            truncation_length = 5
+        "#,
+        );
+        let config = DirectoryConfig::load("directory", Some(&table));
+        assert_eq!(config.truncation_length, 5);
+        assert_eq!(check_module_config("directory", &table), Some(Vec::new()));
+
+        let problems = check_module_config("directory", &self::table("path = 1"));
+        let expected = (
+            String::from("path"),
+            OptionError::Invalid(String::from("expected a string")),
+        );
+        assert_eq!(problems, Some(vec![expected]));
+
+        assert!(is_settable_segment("directory", "path"));
+        assert!(is_settable_segment("git_status", "ahead"));
+        assert!(!is_settable_segment("directory", "ahead"));
+    }
+
+    #[test]
+    fn load_options() {
+        let table = table(
+            r#"
+            truncation_length = 5
+            style = "underline 120"
+            format = "$path"
+        "#,
+        );
+        let config = DirectoryConfig::load("directory", Some(&table));
+        assert_eq!(config.truncation_length, 5);
+        assert_eq!(config.style, Color::Fixed(120).underline());
+        assert!(config.truncate_to_repo);
+    }
+
+    #[test]
+    fn default_tables() {
+        let directory = default_module_config("directory").unwrap();
+        assert_eq!(directory.get("truncation_length"), Some(&Value::Integer(3)));
+        assert_eq!(
+            directory.get("style"),
+            Some(&Value::String(String::from("bold fg:cyan")))
+        );
+
+        // Options without a default are left out
+        let custom = default_module_config("custom.foo").unwrap();
+        assert_eq!(custom.get("command"), None);
+        assert_eq!(default_module_config("unknown"), None);
+    }
+
+    #[test]
+    fn load_ignores_invalid_options() {
+        let table = table(
+            r#"
+            truncation_length = "5"
+            truncate_to_repo = false
+            style = "bold rde"
+            unknown = 1
+        "#,
+        );
+        let config = DirectoryConfig::load("directory", Some(&table));
+        assert_eq!(config.truncation_length, 3);
+        assert!(!config.truncate_to_repo);
+        assert_eq!(config.style, Color::Cyan.bold());
+    }
+
+    #[test]
+    fn threshold_styles() {
+        let display = vec![
+            ThresholdStyle {
+                threshold: 30,
+                style: Color::Red.bold(),
+            },
+            ThresholdStyle {
+                threshold: 10,
+                style: Color::Yellow.normal(),
+            },
+        ];
+
+        assert_eq!(style_at_or_above(&display, 5.0), None);
+        assert_eq!(
+            style_at_or_above(&display, 10.0),
+            Some(Color::Yellow.normal())
+        );
+        assert_eq!(style_at_or_above(&display, 45.0), Some(Color::Red.bold()));
+
+        assert_eq!(
+            style_at_or_below(&display, 5.0),
+            Some(Color::Yellow.normal())
+        );
+        assert_eq!(style_at_or_below(&display, 20.5), Some(Color::Red.bold()));
+        assert_eq!(style_at_or_below(&display, 30.5), None);
+    }
+
+    #[test]
+    fn load_style_with_none() {
+        let config = DirectoryConfig::load("directory", Some(&table(r#"style = "bold none""#)));
+        assert_eq!(config.style, ansi_term::Style::default());
+    }
+
+    #[test]
+    fn check_options() {
+        let problems = DirectoryConfig::check(&table(
+            r#"
+            truncation_length = "5"
+            style = "bold rde"
+            unknown = 1
+            format = "$path"
+        "#,
+        ));
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    "style".to_string(),
+                    OptionError::Invalid(
+                        "invalid style \"bold rde\": unknown color \"rde\"".to_string()
+                    )
+                ),
+                (
+                    "truncation_length".to_string(),
+                    OptionError::Invalid("invalid type: string \"5\", expected i64".to_string())
+                ),
+                ("unknown".to_string(), OptionError::Unknown),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_unknown_module() {
+        assert_eq!(check_module_config("dirctory", &Table::new()), None);
+        assert_eq!(
+            check_module_config("custom.foo", &Table::new()),
+            Some(vec![])
+        );
+    }
+}
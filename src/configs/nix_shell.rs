@@ -0,0 +1,30 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `nix_shell` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NixShellConfig {
+    pub use_name: bool,
+    pub pure_msg: String,
+    pub impure_msg: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for NixShellConfig {
+    fn default() -> Self {
+        NixShellConfig {
+            use_name: false,
+            pure_msg: "pure".to_string(),
+            impure_msg: "impure".to_string(),
+            style: Color::Red.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for NixShellConfig {}
@@ -0,0 +1,26 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `nodejs` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodejsConfig {
+    pub symbol: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for NodejsConfig {
+    fn default() -> Self {
+        NodejsConfig {
+            symbol: "⬢ ".to_string(),
+            style: Color::Green.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for NodejsConfig {}
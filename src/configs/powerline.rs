@@ -0,0 +1,36 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the powerline style, where each module is drawn on its
+/// own background
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerlineConfig {
+    /// The glyph between the modules of the left prompt
+    pub separator: String,
+
+    /// The glyph between the modules of the right prompt
+    pub right_separator: String,
+
+    /// The style of the text of modules whose color is used as their background
+    #[serde(with = "style_string")]
+    pub text_style: Style,
+
+    /// Powerline is disabled unless explicitly enabled
+    pub disabled: bool,
+}
+
+impl Default for PowerlineConfig {
+    fn default() -> Self {
+        PowerlineConfig {
+            separator: "\u{e0b0}".to_string(),
+            right_separator: "\u{e0b2}".to_string(),
+            text_style: Color::Black.normal(),
+            disabled: true,
+        }
+    }
+}
+
+impl ModuleConfig for PowerlineConfig {}
@@ -0,0 +1,30 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `python` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PythonConfig {
+    pub symbol: String,
+    pub pyenv_version_name: bool,
+    pub pyenv_prefix: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for PythonConfig {
+    fn default() -> Self {
+        PythonConfig {
+            symbol: "🐍 ".to_string(),
+            pyenv_version_name: false,
+            pyenv_prefix: "pyenv ".to_string(),
+            style: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for PythonConfig {}
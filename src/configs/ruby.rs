@@ -0,0 +1,26 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `ruby` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RubyConfig {
+    pub symbol: String,
+    #[serde(with = "style_string")]
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl Default for RubyConfig {
+    fn default() -> Self {
+        RubyConfig {
+            symbol: "💎 ".to_string(),
+            style: Color::Red.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for RubyConfig {}
@@ -0,0 +1,38 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `time` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeConfig {
+    #[serde(rename = "12hr")]
+    pub use_12hr: bool,
+    pub time_format: Option<String>,
+    #[serde(with = "style_string")]
+    pub style: Style,
+
+    /// The time module is disabled unless explicitly enabled
+    pub disabled: bool,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        TimeConfig {
+            use_12hr: false,
+            time_format: None,
+            style: Color::Yellow.bold(),
+            disabled: true,
+        }
+    }
+}
+
+impl ModuleConfig for TimeConfig {}
+
+/// Whether the `format` of the `time` module is a strftime format, which it was
+/// before format strings replaced it with `time_format`. A format string without
+/// variables would never show the time.
+pub fn is_strftime_format(format: &str) -> bool {
+    format.contains('%') && !format.contains('$')
+}
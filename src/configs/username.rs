@@ -0,0 +1,29 @@
+use ansi_term::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+use super::{style_string, ModuleConfig};
+
+/// The configuration of the `username` module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UsernameConfig {
+    pub show_always: bool,
+    #[serde(with = "style_string")]
+    pub style_root: Style,
+    #[serde(with = "style_string")]
+    pub style_user: Style,
+    pub disabled: bool,
+}
+
+impl Default for UsernameConfig {
+    fn default() -> Self {
+        UsernameConfig {
+            show_always: false,
+            style_root: Color::Red.bold(),
+            style_user: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl ModuleConfig for UsernameConfig {}
@@ -0,0 +1,839 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use toml::value::{Table, Value};
+
+use crate::config::{
+    config_files, get_config_path, load_palette, merge_layer, parse_color_string,
+    read_config_files, remove_custom_modules, Config, ConfigError, ConfigLayer,
+};
+use crate::config_editor;
+use crate::configs::time::is_strftime_format;
+use crate::configs::{self, OptionError};
+use crate::context::{DEFAULT_COMMAND_TIMEOUT, DEFAULT_SCAN_TIMEOUT};
+use crate::formatter::parse_format;
+use crate::module::ALL_MODULES;
+use crate::print::{DEFAULT_MODULE_PRIORITY, DEFAULT_PROMPT_ORDER};
+
+/// The type expected of a configuration option
+#[derive(Clone, Copy)]
+enum OptionType {
+    Boolean,
+    Integer,
+    Format,
+    /// An array of module names, e.g. `prompt_order`
+    ModuleList,
+    /// The name of a palette in `[palettes]`
+    Palette,
+    /// One of a fixed set of strings
+    OneOf(&'static [&'static str]),
+}
+
+use OptionType::*;
+
+const ROOT_OPTIONS: &[(&str, OptionType)] = &[
+    ("add_newline", Boolean),
+    ("prompt_order", ModuleList),
+    ("right_prompt_order", ModuleList),
+    ("module_priority", ModuleList),
+    ("command_timeout", Integer),
+    ("scan_timeout", Integer),
+    ("render_timeout", Integer),
+    ("version_cache", Boolean),
+    ("palette", Palette),
+    ("color_depth", OneOf(&["auto", "truecolor", "256", "16"])),
+];
+
+/// Tables configuring the prompt which aren't modules of `prompt_order`
+const OTHER_TABLES: &[&str] = &["continuation_prompt", "powerline"];
+
+/// Validates the configuration files applying to the current directory, printing
+/// every problem found. Exits with a non-zero status code if there are any.
+pub fn check_config() {
+    let current_dir = env::current_dir().unwrap_or_default();
+    let config_files = config_files(&current_dir);
+    if config_files.is_empty() {
+        println!("Unable to find the configuration file");
+        process::exit(1);
+    }
+
+    let mut problem_count = 0;
+    let mut configs = Vec::new();
+    for (layer, config_path) in config_files {
+        // Included files are checked along with the file including them
+        match read_config_files(&config_path) {
+            Ok(files) => configs.extend(
+                files
+                    .into_iter()
+                    .map(|(path, config)| (layer, path, config)),
+            ),
+            Err(ConfigError::Syntax(path, error)) => {
+                println!(
+                    "{}",
+                    describe_syntax_error(&path.display().to_string(), &error)
+                );
+                problem_count += 1;
+            }
+            Err(error) => {
+                println!("{}", error);
+                problem_count += 1;
+            }
+        }
+    }
+
+    // A file may refer to palettes or custom modules defined in another file
+    let mut merged = Table::new();
+    for (layer, _, config) in &configs {
+        merge_layer(&mut merged, *layer, config.clone());
+    }
+    load_palette(&merged);
+
+    for (layer, config_path, config) in &configs {
+        let mut problems = check_table(config, &merged);
+        if *layer == ConfigLayer::Directory && remove_custom_modules(&mut config.clone()) {
+            problems.push("custom modules are ignored in per-directory files".to_string());
+        }
+
+        if problems.is_empty() {
+            println!("{}: no problems found", config_path.display());
+        }
+        for problem in &problems {
+            println!("{}: {}", config_path.display(), problem);
+        }
+        problem_count += problems.len();
+    }
+
+    if problem_count > 0 {
+        println!("\nFound {} problem(s)", problem_count);
+        process::exit(1);
+    }
+}
+
+/// Prints an option of the configuration file, e.g. `git_branch.style`. Strings
+/// are printed without quotes, for use in scripts.
+pub fn get_option(key: &str) {
+    let config = Table::config_from_file(&user_config_path()).unwrap_or_default();
+
+    match config_editor::get_option(&config, key) {
+        Some(Value::String(value)) => println!("{}", value),
+        Some(Value::Table(table)) => print!(
+            "{}",
+            toml::to_string(&Value::Table(table.clone()))
+                .expect("Unable to represent the table as TOML")
+        ),
+        Some(value) => println!("{}", config_editor::format_value(value)),
+        None => {
+            eprintln!("\"{}\" is not set", key);
+            process::exit(1);
+        }
+    }
+}
+
+/// Sets an option of the configuration file, creating the file if needed. Values
+/// which aren't valid TOML, such as `bold red`, are taken as strings.
+pub fn set_option(key: &str, value: &str) {
+    let value = config_editor::parse_value(value);
+    edit_config_file(|config| config_editor::set_option(config, key, &value).map(Some));
+}
+
+/// Removes an option or a table from the configuration file
+pub fn unset_option(key: &str) {
+    edit_config_file(|config| config_editor::unset_option(config, key));
+}
+
+/// Opens the configuration file in `$VISUAL` or `$EDITOR`
+pub fn edit_config() {
+    let config_path = user_config_path();
+    let default_editor = if cfg!(windows) { "notepad.exe" } else { "vi" };
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| default_editor.to_string());
+
+    if let Some(config_dir) = config_path.parent() {
+        let _ = fs::create_dir_all(config_dir);
+    }
+    // The editor may come with arguments, e.g. `code --wait`
+    let mut editor_args = editor.split_whitespace();
+    let status = Command::new(editor_args.next().unwrap_or_default())
+        .args(editor_args)
+        .arg(&config_path)
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => process::exit(status.code().unwrap_or(1)),
+        Err(error) => {
+            eprintln!("Unable to run the editor \"{}\": {}", editor, error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints the configuration in effect in the current directory as TOML, with
+/// every option which isn't set at its default, as a starting point for
+/// customizing the prompt. Only the named modules are printed, if any.
+pub fn print_config(modules: &[&str]) {
+    let config = Table::initialize(&env::current_dir().unwrap_or_default());
+    let resolved = resolve_config(&config);
+
+    let printed = if modules.is_empty() {
+        resolved
+    } else {
+        let mut printed = Table::new();
+        for module in modules {
+            match config_editor::get_option(&resolved, module) {
+                // Custom modules are nested in `[custom]`
+                Some(value) => insert_nested(&mut printed, module, value.clone()),
+                None => {
+                    eprintln!("Unknown module \"{}\"", module);
+                    process::exit(1);
+                }
+            }
+        }
+        printed
+    };
+
+    print!(
+        "{}",
+        // Only a `Value` orders the options of each table before its nested tables
+        toml::to_string(&Value::Table(printed))
+            .expect("Unable to represent the configuration as TOML")
+    );
+}
+
+/// Fill in the defaults of the configuration. Profiles have already been
+/// applied, and invalid options are left out, as they're ignored when the
+/// prompt is printed.
+fn resolve_config(config: &Table) -> Table {
+    let mut resolved = Table::new();
+    resolved.insert(String::from("add_newline"), Value::Boolean(true));
+    let right_prompt_order = config
+        .get("right_prompt_order")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let prompt_order = DEFAULT_PROMPT_ORDER
+        .iter()
+        .map(|module| Value::String(module.to_string()))
+        .filter(|module| !right_prompt_order.contains(module))
+        .collect();
+    resolved.insert(String::from("prompt_order"), Value::Array(prompt_order));
+    resolved.insert(String::from("right_prompt_order"), Value::Array(Vec::new()));
+    let module_priority = DEFAULT_MODULE_PRIORITY
+        .iter()
+        .map(|module| Value::String(module.to_string()))
+        .collect();
+    resolved.insert(
+        String::from("module_priority"),
+        Value::Array(module_priority),
+    );
+    resolved.insert(
+        String::from("command_timeout"),
+        Value::Integer(DEFAULT_COMMAND_TIMEOUT as i64),
+    );
+    resolved.insert(
+        String::from("scan_timeout"),
+        Value::Integer(DEFAULT_SCAN_TIMEOUT as i64),
+    );
+    resolved.insert(String::from("version_cache"), Value::Boolean(true));
+    resolved.insert(
+        String::from("color_depth"),
+        Value::String(String::from("auto")),
+    );
+
+    for module in ALL_MODULES.iter().chain(OTHER_TABLES) {
+        resolved.insert(
+            module.to_string(),
+            resolve_module(module, config.get(*module)),
+        );
+    }
+
+    for (key, value) in config {
+        match (key.as_str(), value) {
+            ("profiles", _) => {}
+            ("custom", Value::Table(custom)) => {
+                let custom = custom
+                    .iter()
+                    .map(|(name, value)| {
+                        let module = format!("custom.{}", name);
+                        (name.clone(), resolve_module(&module, Some(value)))
+                    })
+                    .collect();
+                resolved.insert(key.clone(), Value::Table(custom));
+            }
+            ("battery", _) => {
+                resolved.insert(key.clone(), resolve_module(key, Some(value)));
+            }
+            ("palettes", Value::Table(_)) => {
+                resolved.insert(key.clone(), value.clone());
+            }
+            (name, _) => {
+                if let Some((_, option_type)) =
+                    ROOT_OPTIONS.iter().find(|(option, _)| *option == name)
+                {
+                    let mut problems = Vec::new();
+                    check_value(
+                        config,
+                        name,
+                        "the root table",
+                        value,
+                        *option_type,
+                        &mut problems,
+                    );
+                    if problems.is_empty() {
+                        resolved.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+    resolved
+}
+
+fn resolve_module(module: &str, config: Option<&Value>) -> Value {
+    let mut resolved = configs::default_module_config(module).unwrap_or_default();
+
+    if let Some(Value::Table(config)) = config {
+        let problems = configs::check_module_config(module, config).unwrap_or_default();
+        for (key, value) in config {
+            if problems.iter().all(|(problem_key, _)| problem_key != key) {
+                resolved.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Table(resolved)
+}
+
+/// Insert a value at a dotted path, e.g. `custom.foo`
+fn insert_nested(table: &mut Table, key: &str, value: Value) {
+    let mut segments = key.split('.').collect::<Vec<_>>();
+    let last = segments.pop().unwrap_or_default();
+
+    let mut table = table;
+    for segment in segments {
+        let nested = table
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match nested {
+            Value::Table(nested) => nested,
+            _ => return,
+        };
+    }
+    table.insert(last.to_string(), value);
+}
+
+fn user_config_path() -> PathBuf {
+    get_config_path().unwrap_or_else(|| {
+        eprintln!("Unable to find the configuration file");
+        process::exit(1);
+    })
+}
+
+/// Rewrites the configuration file with the result of `edit`, unless it returns
+/// `None` as there's nothing to change
+fn edit_config_file<F>(edit: F)
+where
+    F: FnOnce(&str) -> Result<Option<String>, String>,
+{
+    let config_path = user_config_path();
+    let config = match fs::read_to_string(&config_path) {
+        Ok(config) => config,
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            eprintln!("Unable to read {}: {}", config_path.display(), error);
+            process::exit(1);
+        }
+    };
+
+    let result = edit(&config).and_then(|edited| match edited {
+        Some(edited) => config_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&config_path, edited))
+            .map_err(|error| error.to_string()),
+        None => Ok(()),
+    });
+    if let Err(error) = result {
+        eprintln!("{}: {}", config_path.display(), error);
+        process::exit(1);
+    }
+}
+
+/// Describes a TOML syntax error, prefixed with the file, line and column at
+/// which it occurred
+fn describe_syntax_error(config_path: &str, error: &toml::de::Error) -> String {
+    let message = error.to_string();
+
+    match error.line_col() {
+        Some((line, col)) => {
+            // The message ends with the line number, which is given separately
+            let message = match message.rfind(" at line ") {
+                Some(index) => &message[..index],
+                None => &message,
+            };
+            format!(
+                "{}:{}:{}: syntax error: {}",
+                config_path,
+                line + 1,
+                col + 1,
+                message
+            )
+        }
+        None => format!("{}: syntax error: {}", config_path, message),
+    }
+}
+
+/// Lists the problems of a parsed configuration, one per line. References to
+/// palettes and custom modules are looked up in the merged configuration.
+fn check_table(config: &Table, merged: &Table) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (key, value) in config {
+        if let Some((_, option_type)) = ROOT_OPTIONS.iter().find(|(name, _)| name == key) {
+            check_value(
+                merged,
+                key,
+                "the root table",
+                value,
+                *option_type,
+                &mut problems,
+            );
+        } else if key == "custom" {
+            check_custom_modules(value, &mut problems);
+        } else if key == "palettes" {
+            check_palettes(value, &mut problems);
+        } else if key == "profiles" {
+            check_profiles(value, merged, &mut problems);
+        } else if ALL_MODULES.contains(&key.as_str())
+            || key == "battery"
+            || OTHER_TABLES.contains(&key.as_str())
+        {
+            check_module(key, value, &mut problems);
+        } else {
+            problems.push(format!("unknown module or option \"{}\"", key));
+        }
+    }
+
+    problems
+}
+
+fn check_custom_modules(value: &Value, problems: &mut Vec<String>) {
+    let custom_modules = match value.as_table() {
+        Some(custom_modules) => custom_modules,
+        None => {
+            problems.push(type_mismatch("custom", "the root table", "a table", value));
+            return;
+        }
+    };
+
+    for (name, value) in custom_modules {
+        check_module(&format!("custom.{}", name), value, problems);
+    }
+}
+
+fn check_profiles(value: &Value, merged: &Table, problems: &mut Vec<String>) {
+    let profiles = match value.as_table() {
+        Some(profiles) => profiles,
+        None => {
+            problems.push(type_mismatch(
+                "profiles",
+                "the root table",
+                "a table",
+                value,
+            ));
+            return;
+        }
+    };
+
+    for (name, profile) in profiles {
+        let location = format!("[profiles.{}]", name);
+        let mut profile = match profile.as_table() {
+            Some(profile) => profile.clone(),
+            None => {
+                problems.push(format!(
+                    "{} should be a table, found {}",
+                    location,
+                    profile.type_str()
+                ));
+                continue;
+            }
+        };
+
+        if let Some(when) = profile.remove("when") {
+            check_profile_rules(&when, &location, problems);
+        }
+        // A profile overrides the root table, so it is checked as one
+        problems.extend(
+            check_table(&profile, merged)
+                .into_iter()
+                .map(|problem| format!("{}: {}", location, problem)),
+        );
+    }
+}
+
+fn check_profile_rules(when: &Value, location: &str, problems: &mut Vec<String>) {
+    let rules = match when.as_table() {
+        Some(rules) => rules,
+        None => {
+            problems.push(type_mismatch("when", location, "a table", when));
+            return;
+        }
+    };
+
+    for (rule, value) in rules {
+        match rule.as_str() {
+            "hostname" if !value.is_str() => {
+                problems.push(type_mismatch(rule, location, "a string", value))
+            }
+            "ssh" | "container" if !value.is_bool() => {
+                problems.push(type_mismatch(rule, location, "a boolean", value))
+            }
+            "hostname" | "ssh" | "container" => {}
+            _ => problems.push(format!("unknown profile rule \"{}\" in {}", rule, location)),
+        }
+    }
+}
+
+fn check_palettes(value: &Value, problems: &mut Vec<String>) {
+    let palettes = match value.as_table() {
+        Some(palettes) => palettes,
+        None => {
+            problems.push(type_mismatch(
+                "palettes",
+                "the root table",
+                "a table",
+                value,
+            ));
+            return;
+        }
+    };
+
+    for (name, palette) in palettes {
+        let location = format!("[palettes.{}]", name);
+        let colors = match palette.as_table() {
+            Some(colors) => colors,
+            None => {
+                problems.push(format!(
+                    "{} should be a table, found {}",
+                    location,
+                    palette.type_str()
+                ));
+                continue;
+            }
+        };
+
+        for (color_name, color) in colors {
+            match color.as_str().map(parse_color_string) {
+                Some(Err(error)) => problems.push(format!(
+                    "invalid color for \"{}\" in {}: {}",
+                    color_name, location, error
+                )),
+                Some(Ok(_)) => {}
+                None => problems.push(type_mismatch(color_name, &location, "a color", color)),
+            }
+        }
+    }
+}
+
+/// Checks the table of a module against the module's typed configuration
+fn check_module(module: &str, value: &Value, problems: &mut Vec<String>) {
+    let location = format!("[{}]", module);
+    let table = match value.as_table() {
+        Some(table) => table,
+        None => {
+            problems.push(format!(
+                "{} should be a table, found {}",
+                location,
+                value.type_str()
+            ));
+            return;
+        }
+    };
+
+    match table.get("format") {
+        Some(Value::String(format)) if module == "time" && is_strftime_format(format) => {
+            problems.push(format!(
+                "\"format\" in {} is a strftime format, which is deprecated: rename it to \"time_format\"",
+                location
+            ))
+        }
+        Some(format) => check_value(table, "format", &location, format, Format, problems),
+        None => {}
+    }
+
+    let option_problems = configs::check_module_config(module, table).unwrap_or_default();
+    for (key, error) in option_problems {
+        match error {
+            OptionError::Unknown => {
+                problems.push(format!("unknown option \"{}\" in {}", key, location))
+            }
+            OptionError::Invalid(message) => problems.push(format!(
+                "invalid value for \"{}\" in {}: {}",
+                key, location, message
+            )),
+        }
+    }
+}
+
+fn check_value(
+    config: &Table,
+    key: &str,
+    location: &str,
+    value: &Value,
+    option_type: OptionType,
+    problems: &mut Vec<String>,
+) {
+    match option_type {
+        Boolean if !value.is_bool() => {
+            problems.push(type_mismatch(key, location, "a boolean", value))
+        }
+        Integer if !value.is_integer() => {
+            problems.push(type_mismatch(key, location, "an integer", value))
+        }
+        Format => match value.as_str().map(parse_format) {
+            Some(Err(error)) => problems.push(format!(
+                "invalid format for \"{}\" in {}: {}",
+                key, location, error
+            )),
+            Some(Ok(_)) => {}
+            None => problems.push(type_mismatch(key, location, "a format string", value)),
+        },
+        ModuleList => match value.as_array() {
+            Some(modules) => {
+                for module in modules {
+                    match module.as_str() {
+                        Some(module) if !is_known_module(config, module) => {
+                            problems.push(format!("unknown module \"{}\" in {}", module, key))
+                        }
+                        Some(_) => {}
+                        None => problems.push(type_mismatch(key, location, "module names", module)),
+                    }
+                }
+            }
+            None => problems.push(type_mismatch(
+                key,
+                location,
+                "an array of module names",
+                value,
+            )),
+        },
+        Palette => match value.as_str() {
+            Some(name) if !has_palette(config, name) => {
+                problems.push(format!("unknown palette \"{}\" in {}", name, key))
+            }
+            Some(_) => {}
+            None => problems.push(type_mismatch(key, location, "a palette name", value)),
+        },
+        OneOf(choices) => match value.as_str() {
+            Some(choice) if !choices.contains(&choice) => problems.push(format!(
+                "invalid value \"{}\" for \"{}\" in {}, expected one of: {}",
+                choice,
+                key,
+                location,
+                choices.join(", ")
+            )),
+            Some(_) => {}
+            None => problems.push(type_mismatch(key, location, "a string", value)),
+        },
+        _ => {}
+    }
+}
+
+fn type_mismatch(key: &str, location: &str, expected: &str, value: &Value) -> String {
+    format!(
+        "\"{}\" in {} should be {}, found {}",
+        key,
+        location,
+        expected,
+        value.type_str()
+    )
+}
+
+fn has_palette(config: &Table, name: &str) -> bool {
+    config
+        .get("palettes")
+        .and_then(Value::as_table)
+        .and_then(|palettes| palettes.get(name))
+        .is_some()
+}
+
+/// Whether a module can be used in `prompt_order`
+fn is_known_module(config: &Table, module: &str) -> bool {
+    if ALL_MODULES.contains(&module) || module == "battery" || module == "custom" {
+        return true;
+    }
+
+    let mut parts = module.splitn(2, '.');
+    match (parts.next(), parts.next()) {
+        (Some("custom"), Some(name)) => config
+            .get("custom")
+            .and_then(Value::as_table)
+            .and_then(|custom| custom.get(name))
+            .is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(config: &str) -> Vec<String> {
+        let config = toml::from_str(config).unwrap();
+        check_table(&config, &config)
+    }
+
+    #[test]
+    fn valid_config() {
+        let config = r#"
+            add_newline = false
+            color_depth = "256"
+            prompt_order = ["directory", "custom.foo", "character"]
+
+            [directory]
+            truncation_length = 2
+            style = "bold fg:#ff0000 none"
+
+            [custom.foo]
+            command = "echo foo"
+            files = ["foo.txt"]
+
+            [[battery.display]]
+            threshold = 10
+            style = "red"
+        "#;
+        assert_eq!(check(config), Vec::<String>::new());
+    }
+
+    #[test]
+    fn unknown_modules_and_options() {
+        let config = r#"
+            prompt_order = ["directory", "dirctory", "custom.bar"]
+            unknown = 1
+
+            [directory]
+            truncation_lenght = 2
+        "#;
+        assert_eq!(
+            check(config),
+            vec![
+                "unknown option \"truncation_lenght\" in [directory]",
+                "unknown module \"dirctory\" in prompt_order",
+                "unknown module \"custom.bar\" in prompt_order",
+                "unknown module or option \"unknown\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn palettes() {
+        let config = r##"
+            palette = "missing"
+
+            [palettes.mine]
+            mauve = "#c792ea"
+            broken = "#c792"
+            number = 1
+
+            [palettes.other]
+            red = "196"
+        "##;
+        assert_eq!(
+            check(config),
+            vec![
+                "unknown palette \"missing\" in palette",
+                "invalid color for \"broken\" in [palettes.mine]: invalid hex color \"#c792\", expected #rrggbb or #rgb",
+                "\"number\" in [palettes.mine] should be a color, found integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let config = r#"
+            [profiles.server]
+            when = { hostname = "build-*", ssh = "yes", inside = true }
+            add_newline = "no"
+
+            [profiles.server.directory]
+            truncation_lenght = 2
+
+            [profiles.plain]
+            add_newline = false
+        "#;
+        assert_eq!(
+            check(config),
+            vec![
+                "unknown profile rule \"inside\" in [profiles.server]",
+                "\"ssh\" in [profiles.server] should be a boolean, found string",
+                "[profiles.server]: \"add_newline\" in the root table should be a boolean, found string",
+                "[profiles.server]: unknown option \"truncation_lenght\" in [directory]",
+            ]
+        );
+    }
+
+    #[test]
+    fn strftime_time_format() {
+        let config = r#"
+            [time]
+            format = "[%T]"
+        "#;
+        assert_eq!(
+            check(config),
+            vec!["\"format\" in [time] is a strftime format, which is deprecated: rename it to \"time_format\""]
+        );
+    }
+
+    #[test]
+    fn type_mismatches() {
+        let config = r#"
+            add_newline = "no"
+            color_depth = "24bit"
+
+            [character]
+            use_symbol_for_status = 1
+
+            [custom.foo]
+            files = "foo.txt"
+        "#;
+        assert_eq!(
+            check(config),
+            vec![
+                "\"add_newline\" in the root table should be a boolean, found string",
+                "invalid value for \"use_symbol_for_status\" in [character]: \
+                 invalid type: integer `1`, expected a boolean",
+                "invalid value \"24bit\" for \"color_depth\" in the root table, \
+                 expected one of: auto, truecolor, 256, 16",
+                "invalid value for \"files\" in [custom.foo]: \
+                 invalid type: string \"foo.txt\", expected a sequence",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_styles_and_formats() {
+        let config = r#"
+            [aws]
+            style = "bold rde"
+            format = "[$profile"
+        "#;
+        assert_eq!(
+            check(config),
+            vec![
+                "invalid format for \"format\" in [aws]: Unclosed `[` at position 0",
+                "invalid value for \"style\" in [aws]: invalid style \"bold rde\": unknown color \"rde\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_error_position() {
+        let error = toml::from_str::<Table>("add_newline = false\nprompt_order = [\"a\" \"b\"]\n")
+            .unwrap_err();
+        assert!(describe_syntax_error("starship.toml", &error).starts_with("starship.toml:2:"));
+    }
+}
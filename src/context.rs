@@ -1,5 +1,8 @@
+use crate::cache;
+use crate::colors::ColorDepth;
 use crate::config::Config;
 use crate::module::Module;
+use crate::utils::{self, CommandOutput};
 
 use clap::ArgMatches;
 use git2::{Repository, RepositoryState};
@@ -8,6 +11,13 @@ use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The default time in milliseconds an external command may run for
+pub const DEFAULT_COMMAND_TIMEOUT: u64 = 500;
+
+/// The default time in milliseconds spent listing the files of the current directory
+pub const DEFAULT_SCAN_TIMEOUT: u64 = 30;
 
 /// Context contains data or common methods that may be used by multiple modules.
 /// The data contained within Context will be relevant to this particular rendering
@@ -27,6 +37,12 @@ pub struct Context<'a> {
 
     /// Private field to store Git information for modules who need it
     repo: OnceCell<Repo>,
+
+    /// Whether modules are printed without any styling
+    pub no_color: bool,
+
+    /// The color depth of the terminal, which colors are mapped to
+    pub color_depth: ColorDepth,
 }
 
 impl<'a> Context<'a> {
@@ -47,20 +63,44 @@ impl<'a> Context<'a> {
     where
         T: Into<PathBuf>,
     {
-        let config = toml::value::Table::initialize();
-
         // TODO: Currently gets the physical directory. Get the logical directory.
         let current_dir = Context::expand_tilde(dir.into());
 
+        let config = toml::value::Table::initialize(&current_dir);
+
+        // Following https://no-color.org, `NO_COLOR` is honored when it isn't empty
+        let no_color = arguments.is_present("no_color")
+            || env::var_os("NO_COLOR")
+                .filter(|value| !value.is_empty())
+                .is_some();
+
+        let color_depth = Context::color_depth(&config);
+
         Context {
             config,
             arguments,
             current_dir,
             dir_files: OnceCell::new(),
             repo: OnceCell::new(),
+            no_color,
+            color_depth,
         }
     }
 
+    /// Get the color depth set in `color_depth`, detecting the terminal's
+    /// color depth when it isn't set
+    fn color_depth(config: &toml::value::Table) -> ColorDepth {
+        let name = match config.get_as_str("color_depth") {
+            Some(name) if name != "auto" => name,
+            _ => return ColorDepth::detect(),
+        };
+
+        ColorDepth::from_name(name).unwrap_or_else(|| {
+            log::warn!("Unknown color_depth \"{}\", detecting it instead", name);
+            ColorDepth::detect()
+        })
+    }
+
     /// Convert a `~` in a path to the home directory
     fn expand_tilde(dir: PathBuf) -> PathBuf {
         if dir.starts_with("~") {
@@ -74,7 +114,12 @@ impl<'a> Context<'a> {
     pub fn new_module(&self, name: &str) -> Module {
         let config = self.config.get_module_config(name);
 
-        Module::new(name, config)
+        let mut module = Module::new(name, config);
+        if self.no_color {
+            module.disable_color();
+        }
+        module.set_color_depth(self.color_depth);
+        module
     }
 
     /// Check the `disabled` configuration of the module
@@ -119,17 +164,79 @@ impl<'a> Context<'a> {
             })
     }
 
+    /// Lazily lists the files of the current directory. Listing stops once
+    /// `scan_timeout` has elapsed, leaving the remaining files unscanned.
     pub fn get_dir_files(&self) -> Result<&Vec<PathBuf>, std::io::Error> {
         self.dir_files
             .get_or_try_init(|| -> Result<Vec<PathBuf>, std::io::Error> {
-                let dir_files = fs::read_dir(&self.current_dir)?
-                    .filter_map(Result::ok)
-                    .map(|entry| entry.path())
-                    .collect::<Vec<PathBuf>>();
+                let timeout = self.get_timeout("scan_timeout", DEFAULT_SCAN_TIMEOUT);
+                let start = Instant::now();
+
+                let mut dir_files = Vec::new();
+                for entry in fs::read_dir(&self.current_dir)?.filter_map(Result::ok) {
+                    if start.elapsed() > timeout {
+                        log::warn!(
+                            "Scanning the current directory timed out after {}ms",
+                            timeout.as_millis()
+                        );
+                        break;
+                    }
+                    dir_files.push(entry.path());
+                }
 
                 Ok(dir_files)
             })
     }
+
+    /// Execute an external command, killing it if it runs for longer than
+    /// `command_timeout`
+    pub fn exec_cmd(&self, cmd: &str, args: &[&str]) -> Option<CommandOutput> {
+        let timeout = self.get_timeout("command_timeout", DEFAULT_COMMAND_TIMEOUT);
+        utils::exec_cmd(cmd, args, timeout)
+    }
+
+    /// Execute a command reporting the version of a tool. Unless `version_cache`
+    /// is disabled, its output is cached on disk while the tool's binary and the
+    /// environment variables in `env_vars` are unchanged.
+    pub fn exec_version_cmd(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        env_vars: &[&str],
+    ) -> Option<CommandOutput> {
+        self.exec_project_version_cmd(cmd, args, env_vars, None)
+    }
+
+    /// Execute a command reporting the version of a tool which switches versions
+    /// by the settings of the project it's run in, like `go`. A cached output is
+    /// only used for projects with the same `project_settings`.
+    pub fn exec_project_version_cmd(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        env_vars: &[&str],
+        project_settings: Option<String>,
+    ) -> Option<CommandOutput> {
+        if self.config.get_as_bool("version_cache") == Some(false) {
+            return self.exec_cmd(cmd, args);
+        }
+        cache::cached_output(cmd, args, env_vars, project_settings, || {
+            self.exec_cmd(cmd, args)
+        })
+    }
+
+    /// Get a timeout in milliseconds from the root of the configuration
+    fn get_timeout(&self, key: &str, default: u64) -> Duration {
+        let millis = match self.config.get_as_i64(key) {
+            Some(millis) if millis >= 0 => millis as u64,
+            Some(millis) => {
+                log::debug!("[WARN]: {} should not be negative, found {}", key, millis);
+                default
+            }
+            None => default,
+        };
+        Duration::from_millis(millis)
+    }
 }
 
 pub struct Repo {
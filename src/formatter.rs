@@ -0,0 +1,256 @@
+/// A single element of a parsed module format string
+#[derive(Clone, Debug, PartialEq)]
+pub enum FormatElement {
+    /// Literal text, printed as-is
+    Text(String),
+
+    /// A `$name` reference to the segment(s) of the module with the given name
+    Variable(String),
+
+    /// A `[...](style)` group, whose contents are all painted with `style`
+    TextGroup(TextGroup),
+}
+
+/// A group of format elements sharing a single style
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextGroup {
+    /// The elements contained within the brackets
+    pub format: Vec<FormatElement>,
+
+    /// The raw style string contained within the parentheses
+    pub style: String,
+}
+
+impl FormatElement {
+    /// Collects the names of all variables used within this element
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            FormatElement::Text(_) => Vec::new(),
+            FormatElement::Variable(name) => vec![name.as_str()],
+            FormatElement::TextGroup(group) => group
+                .format
+                .iter()
+                .flat_map(FormatElement::variables)
+                .collect(),
+        }
+    }
+}
+
+/** Parse a module format string. The format string is made up of:
+ - literal text, printed as-is
+ - `$name`         (a variable, replaced by the module's segments of the same name)
+ - `[...](style)`  (a text group, painted with the style string in parentheses,
+   where `$style` refers to the style of the module)
+ - `\`             (escapes the following character, e.g. `\[` or `\$`)
+*/
+pub fn parse_format(format: &str) -> Result<Vec<FormatElement>, String> {
+    let mut parser = Parser {
+        chars: format.chars().collect(),
+        position: 0,
+    };
+
+    let elements = parser.parse_elements()?;
+    match parser.peek() {
+        None => Ok(elements),
+        Some(c) => Err(format!(
+            "Unexpected `{}` at position {} in format string \"{}\"",
+            c, parser.position, format
+        )),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.peek();
+        self.position += 1;
+        next
+    }
+
+    /// Parse elements until the end of the input or an unmatched `]`
+    fn parse_elements(&mut self) -> Result<Vec<FormatElement>, String> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                ']' => break,
+                '\\' => {
+                    self.next();
+                    let escaped = self.next().ok_or_else(|| {
+                        "Format string ends with an unfinished escape".to_string()
+                    })?;
+                    text.push(escaped);
+                }
+                '$' => {
+                    self.next();
+                    push_text(&mut elements, &mut text);
+                    elements.push(FormatElement::Variable(self.parse_variable_name()?));
+                }
+                '[' => {
+                    self.next();
+                    push_text(&mut elements, &mut text);
+                    elements.push(FormatElement::TextGroup(self.parse_text_group()?));
+                }
+                _ => {
+                    self.next();
+                    text.push(c);
+                }
+            }
+        }
+
+        push_text(&mut elements, &mut text);
+        Ok(elements)
+    }
+
+    fn parse_variable_name(&mut self) -> Result<String, String> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        if start == self.position {
+            return Err(format!(
+                "Expected a variable name after `$` at position {}",
+                start - 1
+            ));
+        }
+        Ok(self.chars[start..self.position].iter().collect())
+    }
+
+    /// Parse the remainder of a text group, following its opening `[`
+    fn parse_text_group(&mut self) -> Result<TextGroup, String> {
+        let opening = self.position - 1;
+        let format = self.parse_elements()?;
+
+        if self.next() != Some(']') {
+            return Err(format!("Unclosed `[` at position {}", opening));
+        }
+        if self.next() != Some('(') {
+            return Err(format!(
+                "Expected a `(style)` after the text group opened at position {}",
+                opening
+            ));
+        }
+
+        let mut style = String::new();
+        loop {
+            match self.next() {
+                Some(')') => break,
+                Some(c) => style.push(c),
+                None => {
+                    return Err(format!(
+                        "Unclosed style of the text group opened at position {}",
+                        opening
+                    ))
+                }
+            }
+        }
+
+        Ok(TextGroup {
+            format,
+            style: style.trim().to_string(),
+        })
+    }
+}
+
+fn push_text(elements: &mut Vec<FormatElement>, text: &mut String) {
+    if !text.is_empty() {
+        elements.push(FormatElement::Text(std::mem::take(text)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> FormatElement {
+        FormatElement::Text(value.to_string())
+    }
+
+    fn variable(name: &str) -> FormatElement {
+        FormatElement::Variable(name.to_string())
+    }
+
+    #[test]
+    fn parse_text_and_variables() {
+        let parsed = parse_format("on $symbol$branch ").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                text("on "),
+                variable("symbol"),
+                variable("branch"),
+                text(" ")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_text_group() {
+        let parsed = parse_format("via [$symbol $version](bold red) ").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                text("via "),
+                FormatElement::TextGroup(TextGroup {
+                    format: vec![variable("symbol"), text(" "), variable("version")],
+                    style: "bold red".to_string(),
+                }),
+                text(" "),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_nested_text_group() {
+        let parsed = parse_format("[a[b]($style)]()").unwrap();
+        assert_eq!(
+            parsed,
+            vec![FormatElement::TextGroup(TextGroup {
+                format: vec![
+                    text("a"),
+                    FormatElement::TextGroup(TextGroup {
+                        format: vec![text("b")],
+                        style: "$style".to_string(),
+                    }),
+                ],
+                style: "".to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn parse_escaped_characters() {
+        let parsed = parse_format(r"\[\$5\] \\").unwrap();
+        assert_eq!(parsed, vec![text(r"[$5] \")]);
+    }
+
+    #[test]
+    fn parse_variables_in_group() {
+        let parsed = parse_format("[$a ($b)]($style)").unwrap();
+        assert_eq!(parsed[0].variables(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_format("[unclosed").is_err());
+        assert!(parse_format("[no style]").is_err());
+        assert!(parse_format("[unclosed style](bold").is_err());
+        assert!(parse_format("unopened]").is_err());
+        assert!(parse_format("$").is_err());
+        assert!(parse_format("trailing \\").is_err());
+    }
+}
@@ -2,6 +2,10 @@ use std::ffi::OsStr;
 use std::path::Path;
 use std::{env, io};
 
+use toml::value::{Table, Value};
+
+use crate::config::Config;
+
 /* We use a two-phase init here: the first phase gives a simple command to the
 shell. This command evaluates a more complicated script using `source` and
 process substitution.
@@ -142,12 +146,27 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
     if let Some(script) = setup_script {
         // Set up quoting for starship path in case it has spaces.
         let starship_path_string = format!("\"{}\"", starship_path);
-        let script = script.replace("::STARSHIP::", &starship_path_string);
+        let script = script
+            .replace("::STARSHIP::", &starship_path_string)
+            .replace(
+                "::RIGHT_PROMPT::",
+                if has_right_prompt() { "1" } else { "0" },
+            );
         print!("{}", script);
     };
     Ok(())
 }
 
+/// Whether `right_prompt_order` has any modules, as the shell's own right prompt
+/// is left alone otherwise
+fn has_right_prompt() -> bool {
+    let config = Table::initialize(&env::current_dir().unwrap_or_default());
+    matches!(
+        config.get("right_prompt_order").and_then(Value::as_array),
+        Some(modules) if !modules.is_empty()
+    )
+}
+
 /* GENERAL INIT SCRIPT NOTES
 
 Each init script will be passed as-is. Global notes for init scripts are in this
@@ -160,7 +179,7 @@ to avoid the cost of an additional shell fork every shell draw.
 Note that the init scripts are not in their final form--they are processed by
 `starship init` prior to emitting the final form. In this processing, some tokens
 are replaced, e.g. `::STARSHIP::` is replaced by the full path to the
-starship binary.
+starship binary, and `::RIGHT_PROMPT::` by whether the right prompt is configured.
 */
 
 const BASH_INIT: &str = include_str!("starship.bash");
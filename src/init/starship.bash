@@ -33,10 +33,10 @@ starship_precmd() {
     if [[ $STARSHIP_START_TIME ]]; then
         STARSHIP_END_TIME=$(date +%s)
         STARSHIP_DURATION=$((STARSHIP_END_TIME - STARSHIP_START_TIME))
-        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)" --cmd-duration=$STARSHIP_DURATION)"
+        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)" --cmd-duration=$STARSHIP_DURATION --terminal-width="$COLUMNS")"
         unset STARSHIP_START_TIME
     else
-        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)")"
+        PS1="$(::STARSHIP:: prompt --status=$STATUS --jobs="$(jobs -p | wc -l)" --terminal-width="$COLUMNS")"
     fi
     PREEXEC_READY=true;  # Signal that we can safely restart the timer
 }
@@ -66,4 +66,7 @@ fi
 
 # Set up the start time and STARSHIP_SHELL, which controls shell-specific sequences
 STARSHIP_START_TIME=$(date +%s)
-export STARSHIP_SHELL="bash"
\ No newline at end of file
+export STARSHIP_SHELL="bash"
+
+# Set up the continuation prompt, shown when a command spans multiple lines
+PS2="$(::STARSHIP:: prompt --continuation)"
\ No newline at end of file
@@ -9,7 +9,24 @@ function fish_prompt
     # Account for changes in variable name between v2.7 and v3.0
     set -l CMD_DURATION "$CMD_DURATION$cmd_duration"
     set -l starship_duration (math --scale=0 "$CMD_DURATION / 1000")
-    ::STARSHIP:: prompt --status=$exit_code --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    ::STARSHIP:: prompt --status=$exit_code --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p)) --terminal-width=$COLUMNS
+end
+# The right prompt is only defined when `right_prompt_order` has modules, leaving
+# any fish_right_prompt of the user's alone otherwise
+if test ::RIGHT_PROMPT:: = 1
+    function fish_right_prompt
+        switch "$fish_key_bindings"
+            case fish_hybrid_key_bindings fish_vi_key_bindings
+                set keymap "$fish_bind_mode"
+            case '*'
+                set keymap insert
+        end
+        set -l exit_code $status
+        # Account for changes in variable name between v2.7 and v3.0
+        set -l CMD_DURATION "$CMD_DURATION$cmd_duration"
+        set -l starship_duration (math --scale=0 "$CMD_DURATION / 1000")
+        ::STARSHIP:: prompt --right --status=$exit_code --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p)) --terminal-width=$COLUMNS
+    end
 end
 function fish_mode_prompt; end
 export STARSHIP_SHELL="fish"
@@ -1,8 +1,13 @@
 // Lib is present to allow for benchmarking
+pub mod cache;
+mod colors;
 mod config;
+pub mod configs;
 pub mod context;
+mod formatter;
 pub mod module;
 pub mod modules;
+pub mod output;
 pub mod print;
 pub mod segment;
 mod utils;
@@ -1,17 +1,25 @@
 #[macro_use]
 extern crate clap;
 
+mod cache;
+mod colors;
 mod config;
+mod config_editor;
+mod configs;
+mod configure;
 mod context;
+mod formatter;
 mod init;
 mod module;
 mod modules;
+mod output;
 mod print;
 mod segment;
 mod utils;
 
 use crate::module::ALL_MODULES;
 use clap::{App, AppSettings, Arg, SubCommand};
+use std::process;
 
 fn main() {
     pretty_env_logger::init();
@@ -59,10 +67,41 @@ fn main() {
         .help("The number of currently running jobs")
         .takes_value(true);
 
+    let right_prompt_arg = Arg::with_name("right")
+        .long("right")
+        .help("Print the right prompt (instead of the standard left prompt)");
+
+    let continuation_prompt_arg = Arg::with_name("continuation")
+        .long("continuation")
+        .help("Print the continuation prompt (instead of the standard left prompt)")
+        .conflicts_with("right");
+
+    let output_arg = Arg::with_name("output")
+        .long("output")
+        .value_name("FORMAT")
+        .help("The format to print the prompt in")
+        .possible_values(&["ansi", "json", "plain", "tmux", "zsh"])
+        .default_value("ansi");
+
+    let terminal_width_arg = Arg::with_name("terminal_width")
+        .long("terminal-width")
+        .value_name("COLUMNS")
+        .help("The width of the terminal. Modules are dropped from lines which don't fit")
+        .takes_value(true);
+
+    let no_color_arg = Arg::with_name("no_color")
+        .long("no-color")
+        .help("Print the prompt without any colors or styling, as does setting NO_COLOR");
+
     let init_scripts_arg = Arg::with_name("print_full_init")
         .long("print-full-init")
         .help("Print the main initialization script (as opposed to the init stub)");
 
+    let option_arg = Arg::with_name("option")
+        .value_name("OPTION")
+        .help("The dotted path of a configuration option, e.g. `directory.truncation_length`")
+        .required(true);
+
     let matches = App::new("starship")
         .about("The cross-shell prompt for astronauts. ☄🌌️")
         // pull the version number from Cargo.toml
@@ -84,7 +123,12 @@ fn main() {
                 .arg(&path_arg)
                 .arg(&cmd_duration_arg)
                 .arg(&keymap_arg)
-                .arg(&jobs_arg),
+                .arg(&jobs_arg)
+                .arg(&right_prompt_arg)
+                .arg(&continuation_prompt_arg)
+                .arg(&terminal_width_arg)
+                .arg(&output_arg)
+                .arg(&no_color_arg),
         )
         .subcommand(
             SubCommand::with_name("module")
@@ -105,8 +149,75 @@ fn main() {
                 .arg(&path_arg)
                 .arg(&cmd_duration_arg)
                 .arg(&keymap_arg)
+                .arg(&jobs_arg)
+                .arg(&output_arg)
+                .arg(&no_color_arg),
+        )
+        .subcommand(
+            SubCommand::with_name("timings")
+                .about("Prints how long each module of the prompt takes to compute")
+                .arg(&status_code_arg)
+                .arg(&path_arg)
+                .arg(&cmd_duration_arg)
+                .arg(&keymap_arg)
+                .arg(&jobs_arg),
+        )
+        .subcommand(
+            SubCommand::with_name("explain")
+                .about("Explains what each module of the prompt shows")
+                .arg(&status_code_arg)
+                .arg(&path_arg)
+                .arg(&cmd_duration_arg)
+                .arg(&keymap_arg)
                 .arg(&jobs_arg),
         )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Reads, changes and checks the configuration file")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("check")
+                        .about("Reports syntax errors, unknown options and invalid values"),
+                )
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Prints an option, e.g. `git_branch.style`")
+                        .arg(&option_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Sets an option, keeping the comments of the file")
+                        .arg(&option_arg)
+                        .arg(
+                            Arg::with_name("value")
+                                .help("The value of the option, as TOML or as a plain string")
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("unset")
+                        .about("Removes an option or a table")
+                        .arg(&option_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("edit").about("Opens the configuration file in $EDITOR"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("print-config")
+                .about("Prints the configuration in effect, including the defaults of every option")
+                .arg(
+                    Arg::with_name("name")
+                        .help("The modules to print, e.g. `directory` or `custom.foo`")
+                        .multiple(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .about("Manages the cache of tool versions")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(SubCommand::with_name("clear").about("Removes all cached versions")),
+        )
         .get_matches();
 
     match matches.subcommand() {
@@ -131,6 +242,34 @@ fn main() {
                 print::module(module_name, sub_m.clone());
             }
         }
+        ("timings", Some(sub_m)) => print::timings(sub_m.clone()),
+        ("explain", Some(sub_m)) => print::explain(sub_m.clone()),
+        ("config", Some(sub_m)) => match sub_m.subcommand() {
+            ("check", Some(_)) => configure::check_config(),
+            ("get", Some(sub_m)) => configure::get_option(sub_m.value_of("option").unwrap()),
+            ("set", Some(sub_m)) => configure::set_option(
+                sub_m.value_of("option").unwrap(),
+                sub_m.value_of("value").unwrap(),
+            ),
+            ("unset", Some(sub_m)) => configure::unset_option(sub_m.value_of("option").unwrap()),
+            ("edit", Some(_)) => configure::edit_config(),
+            _ => {}
+        },
+        ("print-config", Some(sub_m)) => {
+            let modules = sub_m
+                .values_of("name")
+                .map(Iterator::collect)
+                .unwrap_or_else(Vec::new);
+            configure::print_config(&modules);
+        }
+        ("cache", Some(sub_m)) => {
+            if let ("clear", Some(_)) = sub_m.subcommand() {
+                if let Err(error) = cache::clear() {
+                    eprintln!("Unable to clear the version cache: {}", error);
+                    process::exit(1);
+                }
+            }
+        }
         _ => {}
     }
 }
@@ -1,7 +1,12 @@
-use crate::config::Config;
+use crate::colors::ColorDepth;
+use crate::config::{parse_style_string, style_to_string, Config};
+use crate::configs::{self, ModuleConfig};
+use crate::formatter::{parse_format, FormatElement, TextGroup};
+use crate::output::Output;
 use crate::segment::Segment;
+use ansi_term::ANSIString;
 use ansi_term::Style;
-use ansi_term::{ANSIString, ANSIStrings};
+use serde_json::json;
 use std::fmt;
 
 // List of all modules
@@ -13,6 +18,7 @@ pub const ALL_MODULES: &[&str] = &[
     "cmd_duration",
     "directory",
     "env_var",
+    "fill",
     "git_branch",
     "git_state",
     "git_status",
@@ -38,7 +44,7 @@ pub struct Module<'a> {
     config: Option<&'a toml::value::Table>,
 
     /// The module's name, to be used in configuration and logging.
-    _name: String,
+    name: String,
 
     /// The styling to be inherited by all segments contained within this module.
     style: Style,
@@ -51,23 +57,52 @@ pub struct Module<'a> {
 
     /// The suffix used to separate the current module from the next one.
     suffix: Affix,
+
+    /// The user-provided layout of the module. Replaces the prefix, suffix
+    /// and segment order when set.
+    format: Option<Vec<FormatElement>>,
+
+    /// Whether the module is rendered without any styling
+    no_color: bool,
+
+    /// The color depth the module's colors are mapped to when rendered
+    color_depth: ColorDepth,
 }
 
 impl<'a> Module<'a> {
     /// Creates a module with no segments.
     pub fn new(name: &str, config: Option<&'a toml::value::Table>) -> Module<'a> {
+        let format = config
+            .and_then(|config| config.get_as_str("format"))
+            .and_then(|format| match parse_format(format) {
+                Ok(format) => Some(format),
+                Err(error) => {
+                    log::debug!("Unable to parse format of module {}: {}", name, error);
+                    None
+                }
+            });
+
         Module {
             config,
-            _name: name.to_string(),
+            name: name.to_string(),
             style: Style::default(),
             prefix: Affix::default_prefix(name),
             segments: Vec::new(),
             suffix: Affix::default_suffix(name),
+            format,
+            no_color: false,
+            color_depth: ColorDepth::TrueColor,
         }
     }
 
     /// Get a reference to a newly created segment in the module
     pub fn new_segment(&mut self, name: &str, value: &str) -> &mut Segment {
+        debug_assert!(
+            configs::is_settable_segment(&self.name, name),
+            "The segment \"{}\" of {} can't be set from its table, add it to `configs::segment_overrides`",
+            name,
+            self.name
+        );
         let mut segment = Segment::new(name);
         segment.set_style(self.style);
         // Use the provided value unless overwritten by config
@@ -77,18 +112,19 @@ impl<'a> Module<'a> {
         self.segments.last_mut().unwrap()
     }
 
-    /// Should config exists, get a reference to a newly created segment in the module
-    pub fn new_segment_if_config_exists(&mut self, name: &str) -> Option<&mut Segment> {
-        // Use the provided value unless overwritten by config
-        if let Some(value) = self.config_value_str(name) {
-            let mut segment = Segment::new(name);
-            segment.set_style(self.style);
-            segment.set_value(value);
-            self.segments.push(segment);
-            Some(self.segments.last_mut().unwrap())
-        } else {
-            None
-        }
+    /// Gets the name of the module
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the segments of the module
+    pub fn get_segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Gets the segments of the module, to change them once it has been computed
+    pub fn get_segments_mut(&mut self) -> &mut [Segment] {
+        &mut self.segments
     }
 
     /// Whether a module has non-empty segments
@@ -117,107 +153,176 @@ impl<'a> Module<'a> {
         self
     }
 
+    /// Render the module without any styling, keeping all of its text
+    pub fn disable_color(&mut self) -> &mut Module<'a> {
+        self.no_color = true;
+        self
+    }
+
+    /// Map the module's colors to the nearest ones of the given color depth
+    /// when rendered
+    pub fn set_color_depth(&mut self, color_depth: ColorDepth) -> &mut Module<'a> {
+        self.color_depth = color_depth;
+        self
+    }
+
+    /// Gets the style inherited by the module's segments
+    pub fn get_style(&self) -> Style {
+        self.style
+    }
+
+    /// Describes the module as JSON: its name, along with the value and style
+    /// string of its prefix, segments and suffix
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "prefix": self.prefix.to_json(),
+            "segments": self.segments.iter().map(Segment::to_json).collect::<Vec<_>>(),
+            "suffix": self.suffix.to_json(),
+        })
+    }
+
     /// Returns a vector of colored ANSIString elements to be later used with
     /// `ANSIStrings()` to optimize ANSI codes
     pub fn ansi_strings(&self) -> Vec<ANSIString> {
-        let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
-        let ansi_strings = self
-            .segments
-            .iter()
-            .map(Segment::ansi_string)
-            .collect::<Vec<ANSIString>>();
+        self.ansi_strings_with_prefix(true)
+    }
 
-        let mut ansi_strings = match shell.as_str() {
-            "bash" => ansi_strings_modified(ansi_strings, shell),
-            "zsh" => ansi_strings_modified(ansi_strings, shell),
-            _ => ansi_strings,
-        };
+    /// Renders the module with the given output backend
+    pub fn render(&self, output: Output) -> String {
+        output.render(&self.ansi_strings())
+    }
 
-        ansi_strings.insert(0, self.prefix.ansi_string());
-        ansi_strings.push(self.suffix.ansi_string());
+    /// Renders the module without its prefix. A module rendered from a `format`
+    /// has no separate prefix, so it is rendered in full.
+    pub fn render_without_prefix(&self, output: Output) -> String {
+        output.render(&self.ansi_strings_with_prefix(false))
+    }
+
+    /// Renders the module's text without any styling or shell escapes
+    pub fn to_unstyled_string(&self) -> String {
+        self.render(Output::Plain)
+    }
+
+    /// Returns the colored ANSIString elements of the module, with or without
+    /// its prefix
+    pub fn ansi_strings_with_prefix(&self, with_prefix: bool) -> Vec<ANSIString<'_>> {
+        let ansi_strings = self.styled_ansi_strings(with_prefix);
+        if !self.no_color {
+            return self.color_depth.downgrade_strings(ansi_strings);
+        }
 
         ansi_strings
+            .iter()
+            .map(|ansi_string| ANSIString::from((**ansi_string).to_string()))
+            .collect()
     }
 
-    pub fn to_string_without_prefix(&self) -> String {
-        ANSIStrings(&self.ansi_strings()[1..]).to_string()
+    fn styled_ansi_strings(&self, with_prefix: bool) -> Vec<ANSIString<'_>> {
+        match &self.format {
+            Some(format) => self.format_ansi_strings(format, None),
+            None => {
+                let mut ansi_strings = self
+                    .segments
+                    .iter()
+                    .map(Segment::ansi_string)
+                    .collect::<Vec<ANSIString>>();
+
+                if with_prefix {
+                    ansi_strings.insert(0, self.prefix.ansi_string());
+                }
+                ansi_strings.push(self.suffix.ansi_string());
+                ansi_strings
+            }
+        }
     }
 
-    /// Get a module's config value as a string
-    pub fn config_value_str(&self, key: &str) -> Option<&str> {
-        self.config.and_then(|config| config.get_as_str(key))
+    /// Renders the elements of a format string. Elements within a text group are
+    /// painted with the style of the group rather than their own.
+    fn format_ansi_strings<'b>(
+        &'b self,
+        format: &'b [FormatElement],
+        group_style: Option<Style>,
+    ) -> Vec<ANSIString<'b>> {
+        format
+            .iter()
+            .flat_map(|element| match element {
+                FormatElement::Text(text) => vec![group_style.unwrap_or_default().paint(text)],
+                FormatElement::Variable(name) => self
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.get_name() == name)
+                    .map(|segment| match group_style {
+                        Some(style) => style.paint(segment.get_value()),
+                        None => segment.ansi_string(),
+                    })
+                    .collect(),
+                FormatElement::TextGroup(group) if self.is_group_empty(group) => Vec::new(),
+                FormatElement::TextGroup(group) => {
+                    let style = self.group_style(&group.style).or(group_style);
+                    self.format_ansi_strings(&group.format, style)
+                }
+            })
+            .collect()
     }
 
-    /// Get a module's config value as an int
-    pub fn config_value_i64(&self, key: &str) -> Option<i64> {
-        self.config.and_then(|config| config.get_as_i64(key))
+    /// A text group is empty if it refers to variables, none of which have a value
+    fn is_group_empty(&self, group: &TextGroup) -> bool {
+        let variables = group
+            .format
+            .iter()
+            .flat_map(FormatElement::variables)
+            .collect::<Vec<&str>>();
+
+        !variables.is_empty()
+            && variables.iter().all(|name| {
+                self.segments
+                    .iter()
+                    .filter(|segment| segment.get_name() == *name)
+                    .all(Segment::is_empty)
+            })
     }
 
-    /// Get a module's config value as a bool
-    pub fn config_value_bool(&self, key: &str) -> Option<bool> {
-        self.config.and_then(|config| config.get_as_bool(key))
+    /// Resolve the style of a text group. `$style` refers to the style of the
+    /// module, and an empty style inherits the style of the enclosing group.
+    fn group_style(&self, style: &str) -> Option<Style> {
+        match style {
+            "" => None,
+            "$style" => Some(self.style),
+            style => match parse_style_string(style) {
+                Ok(style) => Some(style),
+                Err(error) => {
+                    log::warn!("Invalid style \"{}\" in format string: {}", style, error);
+                    None
+                }
+            },
+        }
     }
 
-    /// Get a module's config value as a style
-    pub fn config_value_style(&self, key: &str) -> Option<Style> {
-        self.config.and_then(|config| config.get_as_ansi_style(key))
+    /// Load the module's typed configuration. Options which aren't set, or can't
+    /// be loaded, are left at their default.
+    pub fn load_config<T: ModuleConfig>(&self) -> T {
+        T::load(&self.name, self.config)
     }
 
-    /// Get a module's config value as an array
-    pub fn config_value_array(&self, key: &str) -> Option<&Vec<toml::Value>> {
-        self.config.and_then(|config| config.get_as_array(key))
+    /// Drop the `format` of the module, for modules which understand it
+    /// differently
+    pub fn clear_format(&mut self) {
+        self.format = None;
+    }
+
+    /// Get a module's config value as a string
+    pub fn config_value_str(&self, key: &str) -> Option<&str> {
+        self.config.and_then(|config| config.get_as_str(key))
     }
 }
 
 impl<'a> fmt::Display for Module<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let ansi_strings = self.ansi_strings();
-        write!(f, "{}", ANSIStrings(&ansi_strings))
+        write!(f, "{}", self.render(Output::Ansi))
     }
 }
 
-/// Many shells cannot deal with raw unprintable characters (like ANSI escape sequences) and
-/// miscompute the cursor position as a result, leading to strange visual bugs. Here, we wrap these
-/// characters in shell-specific escape codes to indicate to the shell that they are zero-length.
-fn ansi_strings_modified(ansi_strings: Vec<ANSIString>, shell: String) -> Vec<ANSIString> {
-    const ESCAPE_BEGIN: char = '\u{1b}';
-    const MAYBE_ESCAPE_END: char = 'm';
-    ansi_strings
-        .iter()
-        .map(|ansi| {
-            let mut escaped = false;
-            let final_string: String = ansi
-                .to_string()
-                .chars()
-                .map(|x| match x {
-                    ESCAPE_BEGIN => {
-                        escaped = true;
-                        match shell.as_str() {
-                            "bash" => String::from("\u{5c}\u{5b}\u{1b}"), // => \[ESC
-                            "zsh" => String::from("\u{25}\u{7b}\u{1b}"),  // => %{ESC
-                            _ => x.to_string(),
-                        }
-                    }
-                    MAYBE_ESCAPE_END => {
-                        if escaped {
-                            escaped = false;
-                            match shell.as_str() {
-                                "bash" => String::from("m\u{5c}\u{5d}"), // => m\]
-                                "zsh" => String::from("m\u{25}\u{7d}"),  // => m%}
-                                _ => x.to_string(),
-                            }
-                        } else {
-                            x.to_string()
-                        }
-                    }
-                    _ => x.to_string(),
-                })
-                .collect();
-            ANSIString::from(final_string)
-        })
-        .collect::<Vec<ANSIString>>()
-}
-
 /// Module affixes are to be used for the prefix or suffix of a module.
 pub struct Affix {
     /// The affix's name, to be used in configuration and logging.
@@ -271,6 +376,14 @@ impl Affix {
     pub fn ansi_string(&self) -> ANSIString {
         self.style.paint(&self.value)
     }
+
+    /// Describes the affix as JSON, with its value and style string
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "value": self.value,
+            "style": style_to_string(&self.style),
+        })
+    }
 }
 
 impl fmt::Display for Affix {
@@ -282,17 +395,21 @@ impl fmt::Display for Affix {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ansi_term::Color;
 
     #[test]
     fn test_module_is_empty_with_no_segments() {
         let name = "unit_test";
         let module = Module {
             config: None,
-            _name: name.to_string(),
+            name: name.to_string(),
             style: Style::default(),
             prefix: Affix::default_prefix(name),
             segments: Vec::new(),
             suffix: Affix::default_suffix(name),
+            format: None,
+            no_color: false,
+            color_depth: ColorDepth::TrueColor,
         };
 
         assert!(module.is_empty());
@@ -303,13 +420,47 @@ mod tests {
         let name = "unit_test";
         let module = Module {
             config: None,
-            _name: name.to_string(),
+            name: name.to_string(),
             style: Style::default(),
             prefix: Affix::default_prefix(name),
             segments: vec![Segment::new("test_segment")],
             suffix: Affix::default_suffix(name),
+            format: None,
+            no_color: false,
+            color_depth: ColorDepth::TrueColor,
         };
 
         assert!(module.is_empty());
     }
+
+    #[test]
+    fn test_module_to_json() {
+        let name = "unit_test";
+        let mut module = Module::new(name, None);
+        module.set_style(Color::Red.bold());
+        module.new_segment("symbol", "$");
+        module.get_suffix().set_value("");
+
+        assert_eq!(
+            module.to_json(),
+            json!({
+                "name": "unit_test",
+                "prefix": { "value": "via ", "style": "" },
+                "segments": [{ "name": "symbol", "value": "$", "style": "bold fg:red" }],
+                "suffix": { "value": "", "style": "" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_module_without_color() {
+        let name = "unit_test";
+        let mut module = Module::new(name, None);
+        module.set_style(Color::Red.bold());
+        module.new_segment("symbol", "$");
+        module.get_prefix().set_style(Color::Blue);
+        module.disable_color();
+
+        assert_eq!(module.to_string(), "via $ ");
+    }
 }
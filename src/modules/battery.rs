@@ -1,13 +1,9 @@
-use ansi_term::{Color, Style};
-
 use super::{Context, Module};
-use crate::config::Config;
+use crate::configs::battery::BatteryConfig;
+use crate::configs::style_at_or_below;
 
 /// Creates a module for the battery percentage and charging state
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    const BATTERY_FULL: &str = "•";
-    const BATTERY_CHARGING: &str = "⇡";
-    const BATTERY_DISCHARGING: &str = "⇣";
     // TODO: Update when v1.0 printing refactor is implemented to only
     // print escapes in a prompt context.
     let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
@@ -20,37 +16,31 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let BatteryStatus { state, percentage } = battery_status;
 
     let mut module = context.new_module("battery");
+    let config: BatteryConfig = module.load_config();
 
-    // Parse config under `display`
-    let display_styles = get_display_styles(&module);
-    let display_style = display_styles.iter().find(|display_style| {
-        let BatteryDisplayStyle { threshold, .. } = display_style;
-        percentage <= *threshold as f32
-    });
-
-    if let Some(display_style) = display_style {
-        let BatteryDisplayStyle { style, .. } = display_style;
+    let display_style = style_at_or_below(&config.display, f64::from(percentage));
 
+    if let Some(style) = display_style {
         // Set style based on percentage
-        module.set_style(*style);
+        module.set_style(style);
         module.get_prefix().set_value("");
 
         match state {
             battery::State::Full => {
-                module.new_segment("full_symbol", BATTERY_FULL);
+                module.new_segment("full_symbol", &config.full_symbol);
             }
             battery::State::Charging => {
-                module.new_segment("charging_symbol", BATTERY_CHARGING);
+                module.new_segment("charging_symbol", &config.charging_symbol);
             }
             battery::State::Discharging => {
-                module.new_segment("discharging_symbol", BATTERY_DISCHARGING);
+                module.new_segment("discharging_symbol", &config.discharging_symbol);
             }
             battery::State::Unknown => {
                 log::debug!("Unknown detected");
-                module.new_segment_if_config_exists("unknown_symbol")?;
+                module.new_segment("unknown_symbol", config.unknown_symbol.as_ref()?);
             }
             battery::State::Empty => {
-                module.new_segment_if_config_exists("empty_symbol")?;
+                module.new_segment("empty_symbol", config.empty_symbol.as_ref()?);
             }
             _ => {
                 log::debug!("Unhandled battery state `{}`", state);
@@ -70,28 +60,6 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_display_styles(module: &Module) -> Vec<BatteryDisplayStyle> {
-    if let Some(display_configs) = module.config_value_array("display") {
-        let mut display_styles: Vec<BatteryDisplayStyle> = vec![];
-        for display_config in display_configs.iter() {
-            if let toml::Value::Table(config) = display_config {
-                if let Some(display_style) = BatteryDisplayStyle::from_config(config) {
-                    display_styles.push(display_style);
-                }
-            }
-        }
-
-        // Return display styles as long as display array exists, even if it is empty.
-        display_styles
-    } else {
-        // Default display styles: [{ threshold = 10, style = "red bold" }]
-        vec![BatteryDisplayStyle {
-            threshold: 10,
-            style: Color::Red.bold(),
-        }]
-    }
-}
-
 fn get_battery_status() -> Option<BatteryStatus> {
     let battery_manager = battery::Manager::new().ok()?;
     match battery_manager.batteries().ok()?.next() {
@@ -119,19 +87,3 @@ struct BatteryStatus {
     percentage: f32,
     state: battery::State,
 }
-
-#[derive(Clone, Debug)]
-struct BatteryDisplayStyle {
-    threshold: i64,
-    style: Style,
-}
-
-impl BatteryDisplayStyle {
-    /// construct battery display style from toml table
-    pub fn from_config(config: &toml::value::Table) -> Option<BatteryDisplayStyle> {
-        let threshold = config.get_as_i64("threshold")?;
-        let style = config.get_as_ansi_style("style")?;
-
-        Some(BatteryDisplayStyle { threshold, style })
-    }
-}
@@ -1,6 +1,6 @@
-use ansi_term::Color;
-
 use super::{Context, Module};
+use crate::configs::cmd_duration::CmdDurationConfig;
+use crate::configs::style_at_or_above;
 
 /// Outputs the time it took the last command to execute
 ///
@@ -8,6 +8,7 @@ use super::{Context, Module};
 /// execute. Default is two seconds, but can be set by config option `min_time`.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("cmd_duration");
+    let config: CmdDurationConfig = module.load_config();
 
     let arguments = &context.arguments;
     let elapsed = arguments
@@ -16,7 +17,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         .parse::<u64>()
         .ok()?;
 
-    let signed_config_min = module.config_value_i64("min_time").unwrap_or(2);
+    let signed_config_min = config.min_time;
 
     /* TODO: Once error handling is implemented, warn the user if their config
     min time is nonsensical */
@@ -30,14 +31,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let config_min = signed_config_min as u64;
 
-    let module_color = match elapsed {
-        time if time < config_min => return None,
-        _ => module
-            .config_value_style("style")
-            .unwrap_or_else(|| Color::Yellow.bold()),
-    };
+    if elapsed < config_min {
+        return None;
+    }
 
-    module.set_style(module_color);
+    // Slower commands can be styled differently
+    let style = style_at_or_above(&config.display, elapsed as f64).unwrap_or(config.style);
+    module.set_style(style);
     module.new_segment("cmd_duration", &format!("took {}", render_time(elapsed)));
     module.get_prefix().set_value("");
 
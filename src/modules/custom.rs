@@ -0,0 +1,92 @@
+use super::{Context, Module};
+use crate::configs::custom::CustomConfig;
+use crate::utils::CommandOutput;
+
+/// Creates a user-defined module from a `[custom.<name>]` table
+///
+/// Will display the output of `custom.<name>.command` if all of the following criteria are met:
+///     - custom.<name>.disabled is absent or false
+///     - any of custom.<name>.files/extensions/directories is found in the current directory,
+///       or custom.<name>.when exits successfully. If none of them are set, it is always shown.
+///     - the command prints something, if custom.<name>.command is set
+pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module(&format!("custom.{}", name));
+    let config: CustomConfig = module.load_config();
+
+    if !is_active(&config, context) {
+        return None;
+    }
+
+    module.set_style(config.style);
+    module.get_prefix().set_value("with ");
+    if let Some(symbol) = &config.symbol {
+        module.new_segment("symbol", symbol);
+    }
+
+    if let Some(command) = &config.command {
+        let output = exec_in_shell(&config, context, command)?;
+        let output = output.stdout.trim();
+        if output.is_empty() {
+            return None;
+        }
+        module.new_segment("output", output);
+    }
+
+    if module.is_empty() {
+        return None;
+    }
+
+    Some(module)
+}
+
+/// Whether the module's files, extensions or directories are in the current
+/// directory, or its `when` command succeeds
+fn is_active(config: &CustomConfig, context: &Context) -> bool {
+    let files = to_str_vec(&config.files);
+    let extensions = to_str_vec(&config.extensions);
+    let directories = to_str_vec(&config.directories);
+
+    if files.is_empty() && extensions.is_empty() && directories.is_empty() && config.when.is_none()
+    {
+        return true;
+    }
+
+    if let Some(scan) = context.try_begin_scan() {
+        let is_match = scan
+            .set_files(&files)
+            .set_extensions(&extensions)
+            .set_folders(&directories)
+            .is_match();
+        if is_match {
+            return true;
+        }
+    }
+
+    match config
+        .when
+        .as_ref()
+        .and_then(|when| exec_in_shell(config, context, when))
+    {
+        Some(output) => output.success,
+        None => false,
+    }
+}
+
+/// Runs a command with the configured `shell`, passing it the command with `-c`.
+/// Uses `sh` (or `cmd /C` on Windows) by default.
+fn exec_in_shell(config: &CustomConfig, context: &Context, command: &str) -> Option<CommandOutput> {
+    let shell = to_str_vec(&config.shell);
+
+    let (cmd, mut args) = match shell.split_first() {
+        Some((cmd, args)) => (*cmd, [args, &["-c"]].concat()),
+        None if cfg!(windows) => ("cmd", vec!["/C"]),
+        None => ("sh", vec!["-c"]),
+    };
+    args.push(command);
+
+    context.exec_cmd(cmd, &args)
+}
+
+fn to_str_vec(strings: &[String]) -> Vec<&str> {
+    strings.iter().map(String::as_str).collect()
+}
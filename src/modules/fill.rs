@@ -0,0 +1,20 @@
+use super::{Context, Module};
+use crate::configs::fill::FillConfig;
+
+/// Creates a module padding the rest of the line, so that the modules after it
+/// are aligned to the right edge of the terminal
+///
+/// The module holds a single symbol. It is repeated to fill the line once the
+/// other modules of the line have been computed, in `print::prompt`.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("fill");
+    let config: FillConfig = module.load_config();
+
+    module.set_style(config.style);
+    module.get_prefix().set_value("");
+    module.get_suffix().set_value("");
+
+    module.new_segment("symbol", &config.symbol);
+
+    Some(module)
+}
@@ -1,31 +1,22 @@
-use ansi_term::Color;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{Context, Module};
+use crate::configs::git_branch::GitBranchConfig;
 
 /// Creates a module with the Git branch in the current directory
 ///
 /// Will display the branch name if the current directory is a git repo
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    const GIT_BRANCH_CHAR: &str = " ";
-
     let mut module = context.new_module("git_branch");
+    let config: GitBranchConfig = module.load_config();
 
-    let segment_color = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Purple.bold());
-    module.set_style(segment_color);
+    module.set_style(config.style);
     module.get_prefix().set_value("on ");
 
-    let unsafe_truncation_length = module
-        .config_value_i64("truncation_length")
-        .unwrap_or(std::i64::MAX);
-    let truncation_symbol = get_graphemes(
-        module.config_value_str("truncation_symbol").unwrap_or("…"),
-        1,
-    );
+    let unsafe_truncation_length = config.truncation_length;
+    let truncation_symbol = get_graphemes(&config.truncation_symbol, 1);
 
-    module.new_segment("symbol", GIT_BRANCH_CHAR);
+    module.new_segment("symbol", &config.symbol);
 
     // TODO: Once error handling is implemented, warn the user if their config
     // truncation length is nonsensical
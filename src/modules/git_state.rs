@@ -1,8 +1,8 @@
-use ansi_term::Color;
 use git2::RepositoryState;
 use std::path::{Path, PathBuf};
 
 use super::{Context, Module};
+use crate::configs::git_state::GitStateConfig;
 
 /// Creates a module with the state of the git repository at the current directory
 ///
@@ -10,6 +10,7 @@ use super::{Context, Module};
 /// If the progress information is available (e.g. rebasing 3/10), it will show that too.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_state");
+    let config: GitStateConfig = module.load_config();
 
     let repo = context.get_repo().ok()?;
     let repo_root = repo.root.as_ref()?;
@@ -20,10 +21,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let module_style = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Yellow.bold());
-    module.set_style(module_style);
+    module.set_style(config.style);
     module.get_prefix().set_value("(");
     module.get_suffix().set_value(") ");
 
@@ -34,11 +32,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         _ => panic!("Expected to have a label at this point in the control flow."),
     };
 
-    module.new_segment(label.segment_name, label.message_default);
+    module.new_segment(label.segment_name, label.message(&config));
 
     if let StateDescription::LabelAndProgress(_, progress) = state_description {
         module.new_segment("progress_current", &format!(" {}", progress.current));
-        module.new_segment("progress_divider", "/");
+        module.new_segment("progress_divider", &config.progress_divider);
         module.new_segment("progress_total", &format!("{}", progress.total));
     }
 
@@ -47,37 +45,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 static MERGE_LABEL: StateLabel = StateLabel {
     segment_name: "merge",
-    message_default: "MERGING",
 };
 
 static REVERT_LABEL: StateLabel = StateLabel {
     segment_name: "revert",
-    message_default: "REVERTING",
 };
 
 static CHERRY_LABEL: StateLabel = StateLabel {
     segment_name: "cherry_pick",
-    message_default: "CHERRY-PICKING",
 };
 
 static BISECT_LABEL: StateLabel = StateLabel {
     segment_name: "bisect",
-    message_default: "BISECTING",
 };
 
-static AM_LABEL: StateLabel = StateLabel {
-    segment_name: "am",
-    message_default: "AM",
-};
+static AM_LABEL: StateLabel = StateLabel { segment_name: "am" };
 
 static REBASE_LABEL: StateLabel = StateLabel {
     segment_name: "rebase",
-    message_default: "REBASING",
 };
 
 static AM_OR_REBASE_LABEL: StateLabel = StateLabel {
     segment_name: "am_or_rebase",
-    message_default: "AM/REBASE",
 };
 
 /// Returns the state of the current repository
@@ -153,7 +142,21 @@ enum StateDescription {
 
 struct StateLabel {
     segment_name: &'static str,
-    message_default: &'static str,
+}
+
+impl StateLabel {
+    /// The configured message of the label
+    fn message<'a>(&self, config: &'a GitStateConfig) -> &'a str {
+        match self.segment_name {
+            "merge" => &config.merge,
+            "revert" => &config.revert,
+            "cherry_pick" => &config.cherry_pick,
+            "bisect" => &config.bisect,
+            "am" => &config.am,
+            "rebase" => &config.rebase,
+            _ => &config.am_or_rebase,
+        }
+    }
 }
 
 struct StateProgress {
@@ -1,7 +1,8 @@
-use ansi_term::Color;
 use git2::{Repository, Status};
 
 use super::{Context, Module};
+use crate::configs::git_status::GitStatusConfig;
+use crate::configs::style_at_or_above;
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -18,48 +19,31 @@ use super::{Context, Module};
 ///   - `»` — A renamed file has been added to the staging area
 ///   - `✘` — A file's deletion has been added to the staging area
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    // This is the order that the sections will appear in
-    const GIT_STATUS_CONFLICTED: &str = "=";
-    const GIT_STATUS_AHEAD: &str = "⇡";
-    const GIT_STATUS_BEHIND: &str = "⇣";
-    const GIT_STATUS_DIVERGED: &str = "⇕";
-    const GIT_STATUS_UNTRACKED: &str = "?";
-    const GIT_STATUS_STASHED: &str = "$";
-    const GIT_STATUS_MODIFIED: &str = "!";
-    const GIT_STATUS_ADDED: &str = "+";
-    const GIT_STATUS_RENAMED: &str = "»";
-    const GIT_STATUS_DELETED: &str = "✘";
-    const PREFIX: &str = "[";
-    const SUFFIX: &str = "] ";
-
     let repo = context.get_repo().ok()?;
     let branch_name = repo.branch.as_ref()?;
     let repo_root = repo.root.as_ref()?;
     let repository = Repository::open(repo_root).ok()?;
 
     let mut module = context.new_module("git_status");
-    let show_sync_count = module.config_value_bool("show_sync_count").unwrap_or(false);
-    let module_style = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Red.bold());
-    let start_symbol = module
-        .config_value_str("prefix")
-        .unwrap_or(PREFIX)
-        .to_owned();
-    let end_symbol = module
-        .config_value_str("suffix")
-        .unwrap_or(SUFFIX)
-        .to_owned();
+    let config: GitStatusConfig = module.load_config();
+    let show_sync_count = config.show_sync_count;
+
+    let repo_status = get_repo_status(&repository);
+    log::debug!("Repo status: {:?}", repo_status);
+
+    // Repos with more changed files can be styled differently
+    let changed_files = repo_status.as_ref().map_or(0, |(_, count)| *count);
+    let style = style_at_or_above(&config.display, changed_files as f64).unwrap_or(config.style);
 
     module
         .get_prefix()
-        .set_value(start_symbol)
-        .set_style(module_style);
+        .set_value(config.prefix.as_str())
+        .set_style(style);
     module
         .get_suffix()
-        .set_value(end_symbol)
-        .set_style(module_style);
-    module.set_style(module_style);
+        .set_value(config.suffix.as_str())
+        .set_style(style);
+    module.set_style(style);
 
     let ahead_behind = get_ahead_behind(&repository, branch_name);
     if ahead_behind == Ok((0, 0)) {
@@ -75,20 +59,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         log::trace!("No stash object found");
     }
 
-    let repo_status = get_repo_status(&repository);
-    log::debug!("Repo status: {:?}", repo_status);
-
     // Add the conflicted segment
-    if let Ok(repo_status) = repo_status {
+    if let Ok((repo_status, _)) = repo_status {
         if repo_status.is_conflicted() {
-            module.new_segment("conflicted", GIT_STATUS_CONFLICTED);
+            module.new_segment("conflicted", &config.conflicted);
         }
     }
 
     // Add the ahead/behind segment
     if let Ok((ahead, behind)) = ahead_behind {
         let add_ahead = |m: &mut Module<'a>| {
-            m.new_segment("ahead", GIT_STATUS_AHEAD);
+            m.new_segment("ahead", &config.ahead);
 
             if show_sync_count {
                 m.new_segment("ahead_count", &ahead.to_string());
@@ -96,7 +77,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         };
 
         let add_behind = |m: &mut Module<'a>| {
-            m.new_segment("behind", GIT_STATUS_BEHIND);
+            m.new_segment("behind", &config.behind);
 
             if show_sync_count {
                 m.new_segment("behind_count", &behind.to_string());
@@ -104,7 +85,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         };
 
         if ahead > 0 && behind > 0 {
-            module.new_segment("diverged", GIT_STATUS_DIVERGED);
+            module.new_segment("diverged", &config.diverged);
 
             if show_sync_count {
                 add_ahead(&mut module);
@@ -123,29 +104,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     // Add the stashed segment
     if stash_object.is_ok() {
-        module.new_segment("stashed", GIT_STATUS_STASHED);
+        module.new_segment("stashed", &config.stashed);
     }
 
     // Add all remaining status segments
-    if let Ok(repo_status) = repo_status {
+    if let Ok((repo_status, _)) = repo_status {
         if repo_status.is_wt_deleted() || repo_status.is_index_deleted() {
-            module.new_segment("deleted", GIT_STATUS_DELETED);
+            module.new_segment("deleted", &config.deleted);
         }
 
         if repo_status.is_wt_renamed() || repo_status.is_index_renamed() {
-            module.new_segment("renamed", GIT_STATUS_RENAMED);
+            module.new_segment("renamed", &config.renamed);
         }
 
         if repo_status.is_wt_modified() {
-            module.new_segment("modified", GIT_STATUS_MODIFIED);
+            module.new_segment("modified", &config.modified);
         }
 
         if repo_status.is_index_modified() || repo_status.is_index_new() {
-            module.new_segment("staged", GIT_STATUS_ADDED);
+            module.new_segment("staged", &config.staged);
         }
 
         if repo_status.is_wt_new() {
-            module.new_segment("untracked", GIT_STATUS_UNTRACKED);
+            module.new_segment("untracked", &config.untracked);
         }
     }
 
@@ -156,8 +137,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-/// Gets the bitflags associated with the repo's git status
-fn get_repo_status(repository: &Repository) -> Result<Status, git2::Error> {
+/// Gets the bitflags associated with the repo's git status, along with the
+/// number of files which have a status
+fn get_repo_status(repository: &Repository) -> Result<(Status, usize), git2::Error> {
     let mut status_options = git2::StatusOptions::new();
 
     match repository.config()?.get_entry("status.showUntrackedFiles") {
@@ -176,7 +158,7 @@ fn get_repo_status(repository: &Repository) -> Result<Status, git2::Error> {
         return Err(git2::Error::from_str("Repo has no status"));
     }
 
-    Ok(repo_status)
+    Ok((repo_status, repo_file_statuses.len()))
 }
 
 /// Compares the current branch with the branch it is tracking to determine how
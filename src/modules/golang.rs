@@ -1,7 +1,8 @@
-use ansi_term::Color;
-use std::process::Command;
+use std::path::Path;
 
 use super::{Context, Module};
+use crate::configs::golang::GoConfig;
+use crate::utils;
 
 /// Creates a module with the current Go version
 ///
@@ -25,18 +26,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_go_version() {
+    match get_go_version(context) {
         Some(go_version) => {
-            const GO_CHAR: &str = "🐹 ";
-
             let mut module = context.new_module("golang");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Cyan.bold());
-            module.set_style(module_style);
+            let config: GoConfig = module.load_config();
+            module.set_style(config.style);
 
             let formatted_version = format_go_version(&go_version)?;
-            module.new_segment("symbol", GO_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", &formatted_version);
 
             Some(module)
@@ -45,12 +42,36 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_go_version() -> Option<String> {
-    Command::new("go")
-        .arg("version")
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
+fn get_go_version(context: &Context) -> Option<String> {
+    let project_settings = find_toolchain_directives(&context.current_dir);
+    context
+        .exec_project_version_cmd(
+            "go",
+            &["version"],
+            &["GOROOT", "GOTOOLCHAIN"],
+            project_settings,
+        )
+        .map(|output| output.stdout)
+}
+
+/// Get the `go` and `toolchain` directives of the nearest `go.mod`. Since Go
+/// 1.21, `go` switches to the toolchain they ask for, so the version shown
+/// depends on them.
+fn find_toolchain_directives(dir: &Path) -> Option<String> {
+    let go_mod = dir
+        .ancestors()
+        .find_map(|dir| utils::read_file(dir.join("go.mod")).ok())?;
+    let directives = go_mod
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            matches!(
+                line.split_whitespace().next(),
+                Some("go") | Some("toolchain")
+            )
+        })
+        .collect::<Vec<_>>();
+    Some(directives.join("\n"))
 }
 
 fn format_go_version(go_stdout: &str) -> Option<String> {
@@ -74,6 +95,23 @@ fn format_go_version(go_stdout: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn toolchain_directives() -> std::io::Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        let package_dir = project_dir.path().join("cmd");
+        std::fs::create_dir(&package_dir)?;
+        std::fs::write(
+            project_dir.path().join("go.mod"),
+            "module example.com/app\n\ngo 1.21\n\ntoolchain go1.22.1\n\nrequire (\n    golang.org/x/text v0.14.0\n)\n",
+        )?;
+
+        assert_eq!(
+            find_toolchain_directives(&package_dir),
+            Some(String::from("go 1.21\ntoolchain go1.22.1"))
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_format_go_version() {
         let input = "go version go1.12 darwin/amd64";
@@ -1,7 +1,7 @@
-use ansi_term::Color;
 use std::env;
 
 use super::{Context, Module};
+use crate::configs::hostname::HostnameConfig;
 use std::ffi::OsString;
 
 /// Creates a module with the system hostname
@@ -11,12 +11,10 @@ use std::ffi::OsString;
 ///     - hostname.ssh_only is false OR the user is currently connected as an SSH session (`$SSH_CONNECTION`)
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("hostname");
-    let module_style = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Green.bold().dimmed());
+    let config: HostnameConfig = module.load_config();
 
     let ssh_connection = env::var("SSH_CONNECTION").ok();
-    if module.config_value_bool("ssh_only").unwrap_or(true) && ssh_connection.is_none() {
+    if config.ssh_only && ssh_connection.is_none() {
         return None;
     }
 
@@ -30,11 +28,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     };
 
-    let prefix = module.config_value_str("prefix").unwrap_or("").to_owned();
-    let suffix = module.config_value_str("suffix").unwrap_or("").to_owned();
-
-    module.set_style(module_style);
-    module.new_segment("hostname", &format!("{}{}{}", prefix, host, suffix));
+    module.set_style(config.style);
+    module.new_segment(
+        "hostname",
+        &format!("{}{}{}", config.prefix, host, config.suffix),
+    );
     module.get_prefix().set_value("on ");
 
     Some(module)
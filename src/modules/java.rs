@@ -1,8 +1,5 @@
-use std::process::Command;
-
-use ansi_term::Color;
-
 use super::{Context, Module};
+use crate::configs::java::JavaConfig;
 
 /// Creates a module with the current Java version
 ///
@@ -20,18 +17,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_java_version() {
+    match get_java_version(context) {
         Some(java_version) => {
-            const JAVA_CHAR: &str = "☕ ";
-
             let mut module = context.new_module("java");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Red.dimmed());
-            module.set_style(module_style);
+            let config: JavaConfig = module.load_config();
+            module.set_style(config.style);
 
             let formatted_version = format_java_version(java_version)?;
-            module.new_segment("symbol", JAVA_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", &formatted_version);
 
             Some(module)
@@ -40,16 +33,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_java_version() -> Option<String> {
+fn get_java_version(context: &Context) -> Option<String> {
     let java_command = match std::env::var("JAVA_HOME") {
         Ok(java_home) => format!("{}/bin/java", java_home),
         Err(_) => String::from("java"),
     };
 
-    match Command::new(java_command).arg("-Xinternalversion").output() {
-        Ok(output) => Some(String::from_utf8(output.stdout).unwrap()),
-        Err(_) => None,
-    }
+    context
+        .exec_version_cmd(&java_command, &["-Xinternalversion"], &["JAVA_HOME"])
+        .map(|output| output.stdout)
 }
 
 /// Extract the java version from `java_stdout`.
@@ -1,18 +1,11 @@
-use ansi_term::Color;
-
 use super::{Context, Module};
+use crate::configs::jobs::JobsConfig;
+use crate::configs::style_at_or_above;
 
 /// Creates a segment to show if there are any active jobs running
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("jobs");
-
-    let threshold = module.config_value_i64("threshold").unwrap_or(1);
-
-    const JOB_CHAR: &str = "✦";
-    let module_style = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Blue.bold());
-    module.set_style(module_style);
+    let config: JobsConfig = module.load_config();
 
     let arguments = &context.arguments;
     let num_of_jobs = arguments
@@ -24,8 +17,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     if num_of_jobs == 0 {
         return None;
     }
-    module.new_segment("symbol", JOB_CHAR);
-    if num_of_jobs > threshold {
+
+    let style = style_at_or_above(&config.display, num_of_jobs as f64).unwrap_or(config.style);
+    module.set_style(style);
+    module.new_segment("symbol", &config.symbol);
+    if num_of_jobs > config.threshold {
         module.new_segment("number", &num_of_jobs.to_string());
     }
     module.get_prefix().set_value("");
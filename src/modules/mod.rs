@@ -2,8 +2,10 @@
 mod aws;
 mod character;
 mod cmd_duration;
+mod custom;
 mod directory;
 mod env_var;
+mod fill;
 mod git_branch;
 mod git_state;
 mod git_status;
@@ -32,6 +34,7 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "aws" => aws::module(context),
         "directory" => directory::module(context),
         "env_var" => env_var::module(context),
+        "fill" => fill::module(context),
         "character" => character::module(context),
         "nodejs" => nodejs::module(context),
         "rust" => rust::module(context),
@@ -52,6 +55,9 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "nix_shell" => nix_shell::module(context),
         "hostname" => hostname::module(context),
         "time" => time::module(context),
+        custom if custom.starts_with("custom.") => {
+            custom::module(custom.trim_start_matches("custom."), context)
+        }
 
         _ => {
             eprintln!("Error: Unknown module {}. Use starship module --list to list out all supported modules.", module);
@@ -59,3 +65,92 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         }
     }
 }
+
+/// Describes what a module shows, for `starship explain`
+pub fn description(module: &str) -> &'static str {
+    match module {
+        "aws" => "The current AWS profile",
+        "battery" => "The charge and state of the battery",
+        "character" => "Where the text input starts, showing whether the last command succeeded",
+        "cmd_duration" => "How long the last command took to run",
+        "directory" => "The current directory",
+        "env_var" => "The value of an environment variable",
+        "fill" => "Pads the line, aligning the modules after it to the right edge",
+        "git_branch" => "The active branch of the git repository",
+        "git_state" => "The operation in progress in the git repository",
+        "git_status" => "The state of the files in the git repository",
+        "golang" => "The version of Go",
+        "hostname" => "The hostname of the system",
+        "java" => "The version of Java",
+        "jobs" => "The jobs running in the background",
+        "line_break" => "Splits the prompt over two lines",
+        "nix_shell" => "The active nix-shell environment",
+        "nodejs" => "The version of Node.js",
+        "package" => "The version of the package in the current directory",
+        "python" => "The version of Python and the active virtual environment",
+        "ruby" => "The version of Ruby",
+        "rust" => "The version of Rust",
+        "time" => "The current time",
+        "username" => "The active user",
+        _ => "<unknown module>",
+    }
+}
+
+/// Describes what a segment of a module means, for `starship explain`
+pub fn segment_description(module: &str, segment: &str) -> Option<&'static str> {
+    let description = match (module, segment) {
+        ("aws", "profile") => "the active AWS profile",
+        ("battery", "full_symbol") => "the battery is full",
+        ("battery", "charging_symbol") => "the battery is charging",
+        ("battery", "discharging_symbol") => "the battery is discharging",
+        ("battery", "unknown_symbol") => "the state of the battery is unknown",
+        ("battery", "empty_symbol") => "the battery is empty",
+        ("battery", "percentage") => "the charge of the battery",
+        ("character", "symbol") => "the last command succeeded",
+        ("character", "error_symbol") => "the last command failed",
+        ("character", "vicmd_symbol") => "the shell is in vi normal mode",
+        (custom, "output") if custom.starts_with("custom.") => "the output of the command",
+        ("cmd_duration", "cmd_duration") => "how long the last command took",
+        ("directory", "path") => "the current directory, truncated",
+        ("env_var", "env_var") => "the value of the environment variable",
+        ("fill", "symbol") => "padding up to the right edge of the terminal",
+        ("git_branch", "symbol") => "the symbol shown before the branch",
+        ("git_branch", "name") => "the name of the active branch",
+        ("git_state", "merge") => "a merge is in progress",
+        ("git_state", "revert") => "a revert is in progress",
+        ("git_state", "cherry_pick") => "a cherry-pick is in progress",
+        ("git_state", "bisect") => "a bisect is in progress",
+        ("git_state", "am") => "patches are being applied with `git am`",
+        ("git_state", "rebase") => "a rebase is in progress",
+        ("git_state", "am_or_rebase") => "a rebase or `git am` is in progress",
+        ("git_state", "progress_current") => "the current step of the operation",
+        ("git_state", "progress_divider") => "separates the current step from the total",
+        ("git_state", "progress_total") => "the number of steps of the operation",
+        ("git_status", "conflicted") => "this branch has merge conflicts",
+        ("git_status", "ahead") => "this branch is ahead of the branch being tracked",
+        ("git_status", "ahead_count") => "the number of commits ahead",
+        ("git_status", "behind") => "this branch is behind the branch being tracked",
+        ("git_status", "behind_count") => "the number of commits behind",
+        ("git_status", "diverged") => "this branch has diverged from the branch being tracked",
+        ("git_status", "stashed") => "there are stashes in this repo",
+        ("git_status", "deleted") => "files have been deleted",
+        ("git_status", "renamed") => "files have been renamed",
+        ("git_status", "modified") => "there are file modifications in the working directory",
+        ("git_status", "staged") => "changes have been added to the staging area",
+        ("git_status", "untracked") => "there are untracked files in the working directory",
+        ("hostname", "hostname") => "the hostname of the system",
+        ("jobs", "symbol") => "there are jobs running in the background",
+        ("jobs", "number") => "the number of jobs running in the background",
+        ("line_break", "character") => "a line break",
+        ("nix_shell", "nix_shell") => "the state and name of the nix-shell",
+        ("python", "pyenv_prefix") => "the version of Python is managed by pyenv",
+        ("python", "virtualenv") => "the active virtual environment",
+        ("time", "time") => "the current time",
+        ("username", "username") => "the active user",
+        (_, "symbol") => "the symbol of the module",
+        (_, "version") => "the version in use",
+        _ => return None,
+    };
+
+    Some(description)
+}
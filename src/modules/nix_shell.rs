@@ -1,7 +1,7 @@
-use ansi_term::Color;
 use std::env;
 
 use super::{Context, Module};
+use crate::configs::nix_shell::NixShellConfig;
 
 // IN_NIX_SHELL should be "pure" or "impure" but lorri uses "1" for "impure"
 // https://github.com/target/lorri/issues/140
@@ -23,20 +23,21 @@ use super::{Context, Module};
 ///     - impure         // use_name == false in an impure nix-shell
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("nix_shell");
+    let config: NixShellConfig = module.load_config();
 
     env::var("IN_NIX_SHELL")
         .ok()
         .and_then(|shell_type| {
             if shell_type == "1" || shell_type == "impure" {
-                Some(module.config_value_str("impure_msg").unwrap_or("impure"))
+                Some(&config.impure_msg)
             } else if shell_type == "pure" {
-                Some(module.config_value_str("pure_msg").unwrap_or("pure"))
+                Some(&config.pure_msg)
             } else {
                 None
             }
         })
         .map(|shell_type| {
-            if module.config_value_bool("use_name").unwrap_or(false) {
+            if config.use_name {
                 match env::var("name").ok() {
                     Some(name) => format!("{} ({})", name, shell_type),
                     None => shell_type.to_string(),
@@ -46,10 +47,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             }
         })
         .map(|segment| {
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Red.bold());
-            module.set_style(module_style);
+            module.set_style(config.style);
             module.new_segment("nix_shell", &segment);
             module
         })
@@ -1,7 +1,5 @@
-use ansi_term::Color;
-use std::process::Command;
-
 use super::{Context, Module};
+use crate::configs::nodejs::NodejsConfig;
 
 /// Creates a module with the current Node.js version
 ///
@@ -21,18 +19,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_node_version() {
+    match get_node_version(context) {
         Some(node_version) => {
-            const NODE_CHAR: &str = "⬢ ";
-
             let mut module = context.new_module("nodejs");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Green.bold());
-            module.set_style(module_style);
+            let config: NodejsConfig = module.load_config();
+            module.set_style(config.style);
 
             let formatted_version = node_version.trim();
-            module.new_segment("symbol", NODE_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", formatted_version);
 
             Some(module)
@@ -41,9 +35,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_node_version() -> Option<String> {
-    match Command::new("node").arg("--version").output() {
-        Ok(output) => Some(String::from_utf8(output.stdout).unwrap()),
-        Err(_) => None,
-    }
+fn get_node_version(context: &Context) -> Option<String> {
+    context
+        .exec_version_cmd("node", &["--version"], &[])
+        .map(|output| output.stdout)
 }
@@ -1,7 +1,7 @@
 use super::{Context, Module};
+use crate::configs::package::PackageConfig;
 use crate::utils;
 
-use ansi_term::Color;
 use serde_json as json;
 use toml;
 
@@ -11,16 +11,12 @@ use toml;
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     match get_package_version() {
         Some(package_version) => {
-            const PACKAGE_CHAR: &str = "📦 ";
-
             let mut module = context.new_module("package");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Red.bold());
-            module.set_style(module_style);
+            let config: PackageConfig = module.load_config();
+            module.set_style(config.style);
             module.get_prefix().set_value("is ");
 
-            module.new_segment("symbol", PACKAGE_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", &package_version);
 
             Some(module)
@@ -1,10 +1,8 @@
 use std::env;
 use std::path::Path;
-use std::process::Command;
-
-use ansi_term::Color;
 
 use super::{Context, Module};
+use crate::configs::python::PythonConfig;
 
 /// Creates a module with the current Python version
 ///
@@ -33,26 +31,22 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let mut module = context.new_module("python");
-    let pyenv_version_name = module
-        .config_value_bool("pyenv_version_name")
-        .unwrap_or(false);
+    let config: PythonConfig = module.load_config();
 
-    const PYTHON_CHAR: &str = "🐍 ";
-    let module_color = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Yellow.bold());
-    module.set_style(module_color);
-    module.new_segment("symbol", PYTHON_CHAR);
+    module.set_style(config.style);
+    module.new_segment("symbol", &config.symbol);
 
-    select_python_version(pyenv_version_name)
-        .map(|python_version| python_module(module, pyenv_version_name, python_version))
+    select_python_version(context, config.pyenv_version_name)
+        .map(|python_version| python_module(module, &config, python_version))
 }
 
-fn python_module(mut module: Module, pyenv_version_name: bool, python_version: String) -> Module {
-    const PYENV_PREFIX: &str = "pyenv ";
-
-    if pyenv_version_name {
-        module.new_segment("pyenv_prefix", PYENV_PREFIX);
+fn python_module<'a>(
+    mut module: Module<'a>,
+    config: &PythonConfig,
+    python_version: String,
+) -> Module<'a> {
+    if config.pyenv_version_name {
+        module.new_segment("pyenv_prefix", &config.pyenv_prefix);
         module.new_segment("version", &python_version.trim());
     } else {
         let formatted_version = format_python_version(&python_version);
@@ -64,38 +58,33 @@ fn python_module(mut module: Module, pyenv_version_name: bool, python_version: S
     module
 }
 
-fn select_python_version(pyenv_version_name: bool) -> Option<String> {
+fn select_python_version(context: &Context, pyenv_version_name: bool) -> Option<String> {
     if pyenv_version_name {
-        get_pyenv_version()
+        get_pyenv_version(context)
     } else {
-        get_python_version()
+        get_python_version(context)
     }
 }
 
-fn get_pyenv_version() -> Option<String> {
-    Command::new("pyenv")
-        .arg("version-name")
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
+fn get_pyenv_version(context: &Context) -> Option<String> {
+    context
+        .exec_cmd("pyenv", &["version-name"])
+        .map(|output| output.stdout)
 }
 
-fn get_python_version() -> Option<String> {
-    match Command::new("python").arg("--version").output() {
-        Ok(output) => {
-            // We have to check both stdout and stderr since for Python versions
-            // < 3.4, Python reports to stderr and for Python version >= 3.5,
-            // Python reports to stdout
-            if output.stdout.is_empty() {
-                let stderr_string = String::from_utf8(output.stderr).unwrap();
-                Some(stderr_string)
-            } else {
-                let stdout_string = String::from_utf8(output.stdout).unwrap();
-                Some(stdout_string)
-            }
+fn get_python_version(context: &Context) -> Option<String> {
+    let output =
+        context.exec_version_cmd("python", &["--version"], &["VIRTUAL_ENV", "PYENV_VERSION"]);
+    output.map(|output| {
+        // We have to check both stdout and stderr since for Python versions
+        // < 3.4, Python reports to stderr and for Python version >= 3.5,
+        // Python reports to stdout
+        if output.stdout.is_empty() {
+            output.stderr
+        } else {
+            output.stdout
         }
-        Err(_) => None,
-    }
+    })
 }
 
 fn format_python_version(python_stdout: &str) -> String {
@@ -1,7 +1,5 @@
-use ansi_term::Color;
-use std::process::Command;
-
 use super::{Context, Module};
+use crate::configs::ruby::RubyConfig;
 
 /// Creates a module with the current Ruby version
 ///
@@ -19,18 +17,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_ruby_version() {
+    match get_ruby_version(context) {
         Some(ruby_version) => {
-            const RUBY_CHAR: &str = "💎 ";
-
             let mut module = context.new_module("ruby");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Red.bold());
-            module.set_style(module_style);
+            let config: RubyConfig = module.load_config();
+            module.set_style(config.style);
 
             let formatted_version = format_ruby_version(&ruby_version)?;
-            module.new_segment("symbol", RUBY_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", &formatted_version);
 
             Some(module)
@@ -39,11 +33,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_ruby_version() -> Option<String> {
-    match Command::new("ruby").arg("-v").output() {
-        Ok(output) => Some(String::from_utf8(output.stdout).unwrap()),
-        Err(_) => None,
-    }
+fn get_ruby_version(context: &Context) -> Option<String> {
+    context
+        .exec_version_cmd("ruby", &["-v"], &["RBENV_VERSION"])
+        .map(|output| output.stdout)
 }
 
 fn format_ruby_version(ruby_version: &str) -> Option<String> {
@@ -1,7 +1,5 @@
-use ansi_term::Color;
-use std::process::Command;
-
 use super::{Context, Module};
+use crate::configs::rust::RustConfig;
 
 /// Creates a module with the current Rust version
 ///
@@ -19,18 +17,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_rust_version() {
+    match get_rust_version(context) {
         Some(rust_version) => {
-            const RUST_CHAR: &str = "🦀 ";
-
             let mut module = context.new_module("rust");
-            let module_style = module
-                .config_value_style("style")
-                .unwrap_or_else(|| Color::Red.bold());
-            module.set_style(module_style);
+            let config: RustConfig = module.load_config();
+            module.set_style(config.style);
 
             let formatted_version = format_rustc_version(rust_version);
-            module.new_segment("symbol", RUST_CHAR);
+            module.new_segment("symbol", &config.symbol);
             module.new_segment("version", &formatted_version);
 
             Some(module)
@@ -39,11 +33,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_rust_version() -> Option<String> {
-    match Command::new("rustc").arg("--version").output() {
-        Ok(output) => Some(String::from_utf8(output.stdout).unwrap()),
-        Err(_) => None,
-    }
+fn get_rust_version(context: &Context) -> Option<String> {
+    context
+        .exec_version_cmd("rustc", &["--version"], &["RUSTUP_TOOLCHAIN"])
+        .map(|output| output.stdout)
 }
 
 fn format_rustc_version(mut rustc_stdout: String) -> String {
@@ -1,30 +1,34 @@
-use ansi_term::Color;
 use chrono::{DateTime, Local};
 
 use super::{Context, Module};
+use crate::configs::time::{is_strftime_format, TimeConfig};
 
 /// Outputs the current time
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("time");
+    let config: TimeConfig = module.load_config();
 
     // Remove when logic for disabled by default exists
-    if module.config_value_bool("disabled").unwrap_or(true) {
+    if config.disabled {
         return None;
     }
 
-    let module_style = module
-        .config_value_style("style")
-        .unwrap_or_else(|| Color::Yellow.bold());
-    module.set_style(module_style);
+    module.set_style(config.style);
 
-    // Load module settings
-    let is_12hr = module.config_value_bool("12hr").unwrap_or(false);
-
-    let default_format = if is_12hr { "%r" } else { "%T" };
-    let time_format = module
+    let legacy_format = module
         .config_value_str("format")
-        .unwrap_or(default_format)
-        .to_owned();
+        .filter(|format| is_strftime_format(format))
+        .map(String::from);
+    if legacy_format.is_some() {
+        module.clear_format();
+    }
+
+    let default_format = if config.use_12hr { "%r" } else { "%T" };
+    let time_format = config
+        .time_format
+        .as_deref()
+        .or(legacy_format.as_deref())
+        .unwrap_or(default_format);
 
     log::trace!(
         "Timer module is enabled with format string: {}",
@@ -32,7 +36,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     );
 
     let local: DateTime<Local> = Local::now();
-    let formatted_time_string = format_time(&time_format, local);
+    let formatted_time_string = format_time(time_format, local);
     module.new_segment("time", &formatted_time_string);
     module.get_prefix().set_value("at ");
 
@@ -1,8 +1,7 @@
-use ansi_term::{Color, Style};
 use std::env;
-use std::process::Command;
 
 use super::{Context, Module};
+use crate::configs::username::UsernameConfig;
 
 /// Creates a module with the current user's username
 ///
@@ -16,13 +15,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let ssh_connection = env::var("SSH_CONNECTION").ok();
 
     const ROOT_UID: Option<u32> = Some(0);
-    let user_uid = get_uid();
+    let user_uid = get_uid(context);
 
     let mut module = context.new_module("username");
-    let show_always = module.config_value_bool("show_always").unwrap_or(false);
+    let config: UsernameConfig = module.load_config();
 
-    if user != logname || ssh_connection.is_some() || user_uid == ROOT_UID || show_always {
-        let module_style = get_mod_style(user_uid, &module);
+    if user != logname || ssh_connection.is_some() || user_uid == ROOT_UID || config.show_always {
+        let module_style = match user_uid {
+            ROOT_UID => config.style_root,
+            _ => config.style_user,
+        };
         module.set_style(module_style);
         module.new_segment("username", &user?);
 
@@ -32,22 +34,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     None
 }
 
-fn get_uid() -> Option<u32> {
-    match Command::new("id").arg("-u").output() {
-        Ok(output) => String::from_utf8(output.stdout)
-            .map(|uid| uid.trim().parse::<u32>().ok())
-            .ok()?,
-        Err(_) => None,
-    }
-}
-
-fn get_mod_style(user_uid: Option<u32>, module: &Module) -> Style {
-    match user_uid {
-        Some(0) => module
-            .config_value_style("style_root")
-            .unwrap_or_else(|| Color::Red.bold()),
-        _ => module
-            .config_value_style("style_user")
-            .unwrap_or_else(|| Color::Yellow.bold()),
-    }
+fn get_uid(context: &Context) -> Option<u32> {
+    context
+        .exec_cmd("id", &["-u"])
+        .and_then(|output| output.stdout.trim().parse::<u32>().ok())
 }
@@ -0,0 +1,218 @@
+use ansi_term::{ANSIString, ANSIStrings, Color, Style};
+
+/// The backend used to render styled text, chosen with `--output`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Output {
+    /// ANSI escape codes, wrapped in the escapes of the shell in `STARSHIP_SHELL`
+    Ansi,
+
+    /// The text alone, without any styling
+    Plain,
+
+    /// tmux's `#[fg=red,bold]` style syntax, for use in a status line
+    Tmux,
+
+    /// zsh's native `%F{red}%B` prompt sequences
+    Zsh,
+}
+
+impl Output {
+    /// Find the backend of the given name, as passed to `--output`
+    pub fn from_name(name: &str) -> Option<Output> {
+        match name {
+            "ansi" => Some(Output::Ansi),
+            "plain" => Some(Output::Plain),
+            "tmux" => Some(Output::Tmux),
+            "zsh" => Some(Output::Zsh),
+            _ => None,
+        }
+    }
+
+    /// Render a sequence of styled strings
+    pub fn render(self, strings: &[ANSIString]) -> String {
+        match self {
+            Output::Ansi => render_ansi(strings),
+            Output::Plain => strings.iter().map(|string| &**string).collect(),
+            Output::Tmux => strings.iter().map(render_tmux).collect(),
+            Output::Zsh => strings.iter().map(render_zsh).collect(),
+        }
+    }
+}
+
+/// Many shells cannot deal with raw unprintable characters (like ANSI escape sequences) and
+/// miscompute the cursor position as a result, leading to strange visual bugs. Here, we wrap these
+/// characters in shell-specific escape codes to indicate to the shell that they are zero-length.
+fn render_ansi(strings: &[ANSIString]) -> String {
+    let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
+    let (begin, end) = match shell.as_str() {
+        "bash" => ("\\[", "\\]"),
+        "zsh" => ("%{", "%}"),
+        _ => return ANSIStrings(strings).to_string(),
+    };
+
+    strings
+        .iter()
+        .map(|string| {
+            let style = string.style_ref();
+            if style.is_plain() {
+                return string.to_string();
+            }
+
+            format!(
+                "{begin}{}{end}{}{begin}{}{end}",
+                style.prefix(),
+                &**string,
+                style.suffix(),
+                begin = begin,
+                end = end
+            )
+        })
+        .collect()
+}
+
+fn render_tmux(string: &ANSIString) -> String {
+    // `#` starts a tmux format sequence, so it is escaped by doubling it
+    let text = string.replace('#', "##");
+    let style = string.style_ref();
+    if style.is_plain() {
+        return text;
+    }
+
+    let mut attributes = Vec::new();
+    if let Some(color) = style.foreground {
+        attributes.push(format!("fg={}", tmux_color(color)));
+    }
+    if let Some(color) = style.background {
+        attributes.push(format!("bg={}", tmux_color(color)));
+    }
+    let flags = [
+        (style.is_bold, "bold"),
+        (style.is_dimmed, "dim"),
+        (style.is_italic, "italics"),
+        (style.is_underline, "underscore"),
+        (style.is_blink, "blink"),
+        (style.is_reverse, "reverse"),
+        (style.is_hidden, "hidden"),
+        (style.is_strikethrough, "strikethrough"),
+    ];
+    attributes.extend(
+        flags
+            .iter()
+            .filter(|(is_set, _)| *is_set)
+            .map(|(_, name)| name.to_string()),
+    );
+
+    format!("#[{}]{}#[default]", attributes.join(","), text)
+}
+
+fn render_zsh(string: &ANSIString) -> String {
+    // `%` starts a zsh prompt sequence, so it is escaped by doubling it
+    let text = string.replace('%', "%%");
+    let style = string.style_ref();
+
+    let mut start = String::new();
+    let mut reset = String::new();
+    if let Some(color) = style.foreground {
+        start.push_str(&format!("%F{{{}}}", zsh_color(color)));
+        reset.push_str("%f");
+    }
+    if let Some(color) = style.background {
+        start.push_str(&format!("%K{{{}}}", zsh_color(color)));
+        reset.push_str("%k");
+    }
+    if style.is_bold {
+        start.push_str("%B");
+        reset.push_str("%b");
+    }
+    if style.is_underline {
+        start.push_str("%U");
+        reset.push_str("%u");
+    }
+
+    // zsh has no sequences for the remaining attributes, so their escape codes
+    // are used, marked as zero-length
+    let escaped = Style {
+        is_dimmed: style.is_dimmed,
+        is_italic: style.is_italic,
+        is_blink: style.is_blink,
+        is_reverse: style.is_reverse,
+        is_hidden: style.is_hidden,
+        is_strikethrough: style.is_strikethrough,
+        ..Style::default()
+    };
+    if !escaped.is_plain() {
+        start.push_str(&format!("%{{{}%}}", escaped.prefix()));
+        reset.push_str(&format!("%{{{}%}}", escaped.suffix()));
+    }
+
+    format!("{}{}{}", start, text, reset)
+}
+
+fn tmux_color(color: Color) -> String {
+    match color {
+        Color::Fixed(number) => format!("colour{}", number),
+        color => color_name(color),
+    }
+}
+
+fn zsh_color(color: Color) -> String {
+    match color {
+        Color::Fixed(number) => number.to_string(),
+        color => color_name(color),
+    }
+}
+
+/// The name of a color, as understood by both tmux and zsh
+fn color_name(color: Color) -> String {
+    match color {
+        Color::Black => "black".to_string(),
+        Color::Red => "red".to_string(),
+        Color::Green => "green".to_string(),
+        Color::Yellow => "yellow".to_string(),
+        Color::Blue => "blue".to_string(),
+        Color::Purple => "magenta".to_string(),
+        Color::Cyan => "cyan".to_string(),
+        Color::White => "white".to_string(),
+        Color::Fixed(number) => number.to_string(),
+        Color::RGB(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings() -> Vec<ANSIString<'static>> {
+        vec![
+            ANSIString::from("via "),
+            Color::Red.bold().paint("#1"),
+            Color::Fixed(120).on(Color::RGB(0, 128, 255)).paint("100%"),
+            Style::new().italic().underline().paint(" "),
+        ]
+    }
+
+    #[test]
+    fn render_plain() {
+        assert_eq!(Output::Plain.render(&strings()), "via #1100% ");
+    }
+
+    #[test]
+    fn render_tmux() {
+        assert_eq!(
+            Output::Tmux.render(&strings()),
+            "via #[fg=red,bold]##1#[default]\
+             #[fg=colour120,bg=#0080ff]100%#[default]\
+             #[italics,underscore] #[default]"
+        );
+    }
+
+    #[test]
+    fn render_zsh() {
+        assert_eq!(
+            Output::Zsh.render(&strings()),
+            "via %F{red}%B#1%f%b\
+             %F{120}%K{#0080ff}100%%%f%k\
+             %U%{\u{1b}[3m%} %u%{\u{1b}[0m%}"
+        );
+    }
+}
@@ -1,17 +1,31 @@
+use ansi_term::{ANSIString, Color, Style};
 use clap::ArgMatches;
 use rayon::prelude::*;
+use std::cmp::Reverse;
 use std::io::{self, Write};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::config::Config;
+use crate::configs::character::CharacterConfig;
+use crate::configs::continuation_prompt::ContinuationPromptConfig;
+use crate::configs::custom::CustomConfig;
+use crate::configs::powerline::PowerlineConfig;
+use crate::configs::ModuleConfig;
 use crate::context::Context;
 use crate::module::Module;
 use crate::module::ALL_MODULES;
 use crate::modules;
+use crate::output::Output;
+use crate::utils;
 
 // List of default prompt order
 // NOTE: If this const value is changed then Default prompt order subheading inside
 // prompt heading of config docs needs to be updated according to changes made here.
-const DEFAULT_PROMPT_ORDER: &[&str] = &[
+pub const DEFAULT_PROMPT_ORDER: &[&str] = &[
     "username",
     "hostname",
     "directory",
@@ -28,6 +42,7 @@ const DEFAULT_PROMPT_ORDER: &[&str] = &[
     "nix_shell",
     "aws",
     "env_var",
+    "custom",
     "cmd_duration",
     "line_break",
     "jobs",
@@ -37,78 +52,660 @@ const DEFAULT_PROMPT_ORDER: &[&str] = &[
     "character",
 ];
 
-pub fn prompt(args: ArgMatches) {
-    let context = Context::new(args);
+/// The modules kept for longest when the prompt is too wide for the terminal,
+/// starting with the most important
+pub const DEFAULT_MODULE_PRIORITY: &[&str] = &["character", "directory", "git_branch"];
+
+pub fn prompt(args: ArgMatches<'static>) {
+    let context = leak_context(Context::new(args));
     let config = &context.config;
 
     let stdout = io::stdout();
     let mut handle = stdout.lock();
 
+    let is_json = is_json_output(context);
+    let output = output_backend(context);
+
+    if context.arguments.is_present("continuation") {
+        let module = continuation_prompt(context);
+        if is_json {
+            writeln!(handle, "{}", modules_to_json(&[module])).unwrap();
+        } else {
+            write!(handle, "{}", module.render(output)).unwrap();
+        }
+        return;
+    }
+
+    let is_right_prompt = context.arguments.is_present("right");
+
+    let prompt_order = get_module_list(context, is_right_prompt);
+    let mut modules = compute_modules(context, &prompt_order);
+
+    let powerline = PowerlineConfig::load("powerline", config.get_module_config("powerline"));
+    let is_powerline = !powerline.disabled && !context.no_color;
+
+    if let Some(width) = terminal_width(context) {
+        let priority = get_prompt_order(config, "module_priority")
+            .unwrap_or_else(|| DEFAULT_MODULE_PRIORITY.to_vec());
+        // Every module of a powerline prompt is followed, or preceded, by a separator
+        let separator_width = match (is_powerline, is_right_prompt) {
+            (false, _) => 0,
+            (true, false) => utils::display_width(&powerline.separator),
+            (true, true) => utils::display_width(&powerline.right_separator),
+        };
+        modules = fit_to_width(modules, width, separator_width, &priority);
+        expand_fill(&mut modules, width, separator_width);
+    }
+
+    if is_json {
+        writeln!(handle, "{}", modules_to_json(&modules)).unwrap();
+        return;
+    }
+
     // Write a new line before the prompt
-    if config.get_as_bool("add_newline") != Some(false) {
+    if !is_right_prompt && config.get_as_bool("add_newline") != Some(false) {
         writeln!(handle).unwrap();
     }
 
-    let mut prompt_order: Vec<&str> = Vec::new();
+    if is_powerline {
+        let ansi_strings = powerline_ansi_strings(&modules, &powerline, is_right_prompt);
+        let ansi_strings = context.color_depth.downgrade_strings(ansi_strings);
+        write!(handle, "{}", output.render(&ansi_strings)).unwrap();
+        return;
+    }
 
-    // Write out a custom prompt order
-    if let Some(modules) = config.get_as_array("prompt_order") {
-        // if prompt_order = [] use default_prompt_order
-        if !modules.is_empty() {
-            for module in modules {
-                let str_value = module.as_str();
-
-                if let Some(value) = str_value {
-                    if ALL_MODULES.contains(&value) {
-                        prompt_order.push(value);
-                    } else {
-                        log::debug!(
-                            "Expected prompt_order to contain value from {:?}. Instead received {}",
-                            ALL_MODULES,
-                            value,
-                        );
-                    }
-                } else {
-                    log::debug!(
-                        "Expected prompt_order to be an array of strings. Instead received {} of type {}",
-                        module,
-                        module.type_str()
-                    );
-                }
+    let mut printable = modules.iter();
+
+    // Print the first module without its prefix
+    if let Some(first_module) = printable.next() {
+        let module_without_prefix = first_module.render_without_prefix(output);
+        write!(handle, "{}", module_without_prefix).unwrap()
+    }
+
+    // Print all remaining modules
+    printable.for_each(|module| write!(handle, "{}", module.render(output)).unwrap());
+}
+
+/// The width passed with `--terminal-width`, if any
+fn terminal_width(context: &Context) -> Option<usize> {
+    let width = context.arguments.value_of("terminal_width")?;
+    match width.trim().parse::<usize>() {
+        Ok(width) if width > 0 => Some(width),
+        _ => {
+            log::debug!("Ignoring invalid terminal width: {:?}", width);
+            None
+        }
+    }
+}
+
+/// Fits each line of the prompt within the terminal's width. The least important
+/// module of a line which is too wide is shortened by removing the connecting
+/// word before it, and once no module of the line can be shortened, it is dropped
+/// instead.
+fn fit_to_width<'a>(
+    mut modules: Vec<Module<'a>>,
+    width: usize,
+    separator_width: usize,
+    priority: &[&str],
+) -> Vec<Module<'a>> {
+    // Lines left too wide once only their last module is kept. Line breaks are
+    // never dropped, so lines keep their number while modules are removed.
+    let mut finished = Vec::new();
+    loop {
+        let overflowing_line =
+            prompt_lines(&modules)
+                .into_iter()
+                .enumerate()
+                .find(|(number, line)| {
+                    !finished.contains(number)
+                        && line_width(&modules, line, separator_width) > width
+                });
+        let (number, mut line) = match overflowing_line {
+            Some(line) => line,
+            None => break,
+        };
+
+        // Fill modules take up whatever space is left, so they are never dropped
+        line.retain(|&index| !is_fill(&modules[index]));
+        line.sort_by_key(|&index| drop_order(&modules[index], index, priority));
+
+        let shortenable = line
+            .iter()
+            .find(|&&index| index > 0 && has_connecting_word(&modules[index]));
+        if let Some(&index) = shortenable {
+            modules[index].get_prefix().set_value("");
+            continue;
+        }
+
+        // Keep the last module of a line, even if it doesn't fit on its own
+        if line.len() <= 1 {
+            finished.push(number);
+            continue;
+        }
+        let dropped = modules.remove(line[0]);
+        log::debug!("Dropping module {} to fit the terminal", dropped.get_name());
+    }
+
+    modules
+}
+
+/// Pads each line containing `fill` modules to the terminal's width, by
+/// repeating their symbols. The space left on a line is shared between its
+/// fill modules.
+fn expand_fill(modules: &mut [Module], width: usize, separator_width: usize) {
+    for line in prompt_lines(modules) {
+        let fills = line
+            .iter()
+            .copied()
+            .filter(|&index| is_fill(&modules[index]))
+            .collect::<Vec<usize>>();
+        if fills.is_empty() {
+            continue;
+        }
+
+        let space = width.saturating_sub(line_width(modules, &line, separator_width));
+        for (position, &index) in fills.iter().enumerate() {
+            let fill_width = space / fills.len() + usize::from(position < space % fills.len());
+            for segment in modules[index].get_segments_mut() {
+                let value = repeat_to_width(segment.get_value(), fill_width);
+                segment.set_value(value);
+            }
+        }
+    }
+}
+
+/// Repeats the symbol until it is exactly `width` columns wide, padding with
+/// spaces where a wide character wouldn't fit
+fn repeat_to_width(symbol: &str, width: usize) -> String {
+    let mut padding = String::new();
+    let mut padding_width = 0;
+
+    if utils::display_width(symbol) > 0 {
+        for grapheme in symbol.graphemes(true).cycle() {
+            let grapheme_width = utils::display_width(grapheme);
+            if padding_width + grapheme_width > width {
+                break;
             }
+            padding.push_str(grapheme);
+            padding_width += grapheme_width;
+        }
+    }
+
+    padding + &" ".repeat(width - padding_width)
+}
+
+/// Splits the prompt into its lines, as the indices of the modules on each line
+fn prompt_lines(modules: &[Module]) -> Vec<Vec<usize>> {
+    let mut lines = vec![Vec::new()];
+    for (index, module) in modules.iter().enumerate() {
+        if module.get_name() == "line_break" {
+            lines.push(Vec::new());
         } else {
-            prompt_order = DEFAULT_PROMPT_ORDER.to_vec();
+            lines.last_mut().unwrap().push(index);
         }
-    } else {
-        prompt_order = DEFAULT_PROMPT_ORDER.to_vec();
     }
+    lines
+}
 
-    let modules = &prompt_order
+/// The width of a line of the prompt, leaving out its fill modules
+fn line_width(modules: &[Module], line: &[usize], separator_width: usize) -> usize {
+    let text_width: usize = line
+        .iter()
+        .filter(|&&index| !is_fill(&modules[index]))
+        .map(|&index| {
+            // The first module of the prompt is printed without its prefix
+            let text = if index == 0 {
+                modules[index].render_without_prefix(Output::Plain)
+            } else {
+                modules[index].to_unstyled_string()
+            };
+            utils::display_width(&text)
+        })
+        .sum();
+
+    text_width + separator_width * line.len()
+}
+
+fn is_fill(module: &Module) -> bool {
+    module.get_name() == "fill"
+}
+
+/// Renders the modules as powerline segments. Each module is drawn on its own
+/// background, and the separator between two modules leads from the background
+/// of one into the background of the next.
+fn powerline_ansi_strings<'a>(
+    modules: &'a [Module],
+    config: &'a PowerlineConfig,
+    is_right_prompt: bool,
+) -> Vec<ANSIString<'a>> {
+    let mut ansi_strings = Vec::new();
+    let mut previous_background = None;
+
+    for (index, module) in modules.iter().enumerate() {
+        if module.get_name() == "line_break" {
+            if let (false, Some(previous)) = (is_right_prompt, previous_background) {
+                ansi_strings.push(separator_style(previous, None).paint(&config.separator[..]));
+            }
+            ansi_strings.extend(module.ansi_strings());
+            previous_background = None;
+            continue;
+        }
+
+        let module_style = module.get_style();
+        let background = module_style.background.or(module_style.foreground);
+        match (is_right_prompt, previous_background, background) {
+            (false, Some(previous), _) => ansi_strings
+                .push(separator_style(previous, background).paint(&config.separator[..])),
+            (true, _, Some(background)) => ansi_strings.push(
+                separator_style(background, previous_background).paint(&config.right_separator[..]),
+            ),
+            _ => {}
+        }
+
+        // The first module of the prompt is printed without its prefix
+        let module_strings = module.ansi_strings_with_prefix(index > 0);
+        ansi_strings.extend(
+            module_strings
+                .iter()
+                .filter(|ansi_string| !ansi_string.is_empty())
+                .map(|ansi_string| {
+                    let style = match background {
+                        // A module with a background of its own keeps its colors
+                        Some(_) if module_style.background.is_some() => Style {
+                            background,
+                            ..*ansi_string.style_ref()
+                        },
+                        Some(_) => Style {
+                            background,
+                            ..config.text_style
+                        },
+                        None => *ansi_string.style_ref(),
+                    };
+                    style.paint((**ansi_string).to_string())
+                }),
+        );
+        previous_background = background;
+    }
+
+    if let (false, Some(previous)) = (is_right_prompt, previous_background) {
+        ansi_strings.push(separator_style(previous, None).paint(&config.separator[..]));
+    }
+    ansi_strings
+}
+
+/// The style of a separator leading from one background color into the next
+fn separator_style(foreground: Color, background: Option<Color>) -> Style {
+    Style {
+        foreground: Some(foreground),
+        background,
+        ..Style::default()
+    }
+}
+
+/// Orders the modules of a line from the least to the most important. Modules
+/// missing from `module_priority` come first, starting from the right of the line.
+fn drop_order(
+    module: &Module,
+    index: usize,
+    priority: &[&str],
+) -> (Option<Reverse<usize>>, Reverse<usize>) {
+    let rank = priority
+        .iter()
+        .position(|name| *name == module.get_name())
+        .map(Reverse);
+    (rank, Reverse(index))
+}
+
+/// Whether the module's prefix is a word connecting it to the previous module,
+/// like `via `, rather than being part of the module, like git_status's `[`
+fn has_connecting_word(module: &Module) -> bool {
+    let text = module.to_unstyled_string();
+    let text_without_prefix = module.render_without_prefix(Output::Plain);
+    let prefix = &text[..text.len() - text_without_prefix.len()];
+
+    let word = prefix.trim_end();
+    prefix.ends_with(' ') && !word.is_empty() && word.chars().all(char::is_alphabetic)
+}
+
+/// Computes every module of the left and right prompts, and prints how long each
+/// of them took alongside its output, starting with the slowest module
+pub fn timings(args: ArgMatches<'static>) {
+    let context = leak_context(Context::new(args));
+
+    let mut module_list = get_module_list(context, false);
+    module_list.extend(get_module_list(context, true));
+
+    let start = Instant::now();
+    let mut timings = module_list
         .par_iter()
+        .map(|module| {
+            let start = Instant::now();
+            let output = modules::handle(module, context)
+                .map(|module| module.to_unstyled_string())
+                .unwrap_or_default();
+            ModuleTiming {
+                name: module.clone(),
+                duration: start.elapsed(),
+                output,
+            }
+        })
+        .collect::<Vec<ModuleTiming>>();
+    let total = start.elapsed();
+    timings.sort_by_key(|timing| Reverse(timing.duration));
+
+    let rows = timings
+        .iter()
+        .map(|timing| {
+            (
+                timing.name.to_string(),
+                format_duration(timing.duration),
+                // Quoted, so that leading and trailing whitespace is visible
+                format!("\"{}\"", timing.output.replace('\n', "\\n")),
+            )
+        })
+        .collect::<Vec<(String, String, String)>>();
+    let name_width = rows
+        .iter()
+        .map(|row| row.0.len())
+        .chain(Some(6))
+        .max()
+        .unwrap();
+    let duration_width = rows
+        .iter()
+        .map(|row| row.1.len())
+        .chain(Some(8))
+        .max()
+        .unwrap();
+
+    println!(
+        "{:<name_width$}  {:>duration_width$}  Output",
+        "Module",
+        "Duration",
+        name_width = name_width,
+        duration_width = duration_width
+    );
+    for (name, duration, output) in rows {
+        println!(
+            "{:<name_width$}  {:>duration_width$}  {}",
+            name,
+            duration,
+            output,
+            name_width = name_width,
+            duration_width = duration_width
+        );
+    }
+
+    println!("\nTotal: {}", format_duration(total));
+}
+
+struct ModuleTiming {
+    name: String,
+    duration: Duration,
+    output: String,
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Computes the visible modules of the left and right prompts, and prints what
+/// each of them shows, segment by segment
+pub fn explain(args: ArgMatches<'static>) {
+    let context = leak_context(Context::new(args));
+
+    let mut module_list = get_module_list(context, false);
+    module_list.extend(get_module_list(context, true));
+
+    let modules = compute_modules(context, &module_list)
+        .into_iter()
+        .filter(|module| !module.is_empty())
+        .collect::<Vec<Module>>();
+
+    for module in modules {
+        let name = module.get_name();
+        if name.starts_with("custom.") {
+            let config: CustomConfig = module.load_config();
+            let description = config.description.as_deref();
+            println!("{}: {}", name, description.unwrap_or("A custom module"));
+        } else {
+            println!("{}: {}", name, modules::description(name));
+        }
+
+        let segments = module
+            .get_segments()
+            .iter()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                (
+                    format!("\"{}\"", segment.get_value().replace('\n', "\\n")),
+                    segment.get_name(),
+                )
+            })
+            .collect::<Vec<(String, &str)>>();
+        let value_width = segments
+            .iter()
+            .map(|(value, _)| value.width())
+            .max()
+            .unwrap_or_default();
+
+        for (value, segment_name) in segments {
+            // Padded by hand, since `format!` doesn't account for wide characters
+            let padding = " ".repeat(value_width - value.width());
+            match modules::segment_description(name, segment_name) {
+                Some(description) => {
+                    println!("  {}{}  {}: {}", value, padding, segment_name, description)
+                }
+                None => println!("  {}{}  {}", value, padding, segment_name),
+            }
+        }
+    }
+}
+
+/// Lists the enabled modules of the left or right prompt, in the order in which
+/// they're displayed
+fn get_module_list(context: &Context, is_right_prompt: bool) -> Vec<String> {
+    let config = &context.config;
+    let left_prompt_order = get_prompt_order(config, "prompt_order");
+    let right_prompt_order = get_prompt_order(config, "right_prompt_order").unwrap_or_default();
+    let prompt_order = if is_right_prompt {
+        right_prompt_order.clone()
+    } else {
+        // Modules moved to the right prompt are left out of the default prompt order
+        left_prompt_order.clone().unwrap_or_else(|| {
+            DEFAULT_PROMPT_ORDER
+                .iter()
+                .filter(|module| !right_prompt_order.contains(module))
+                .copied()
+                .collect()
+        })
+    };
+
+    // `custom` stands for all custom modules which aren't placed explicitly
+    let is_placed = |module: &str| {
+        right_prompt_order.contains(&module)
+            || left_prompt_order
+                .iter()
+                .any(|order| order.contains(&module))
+    };
+
+    prompt_order
+        .iter()
+        .flat_map(|module| match *module {
+            "custom" => get_custom_modules(config)
+                .into_iter()
+                .filter(|custom| !is_placed(custom))
+                .collect(),
+            module => vec![module.to_string()],
+        })
         .filter(|module| context.is_module_enabled(module))
-        .map(|module| modules::handle(module, &context)) // Compute modules
-        .flatten()
-        .collect::<Vec<Module>>(); // Remove segments set to `None`
+        .collect()
+}
 
-    let mut printable = modules.iter();
+/// Lists the modules defined in `[custom.<name>]` tables, e.g. `custom.foo`
+fn get_custom_modules(config: &toml::value::Table) -> Vec<String> {
+    config
+        .get_module_config("custom")
+        .map(|custom| {
+            custom
+                .keys()
+                .map(|name| format!("custom.{}", name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    // Print the first module without its prefix
-    if let Some(first_module) = printable.next() {
-        let module_without_prefix = first_module.to_string_without_prefix();
-        write!(handle, "{}", module_without_prefix).unwrap()
+/// Creates the prompt shown when a command spans multiple lines. Without a
+/// configured style, it reuses the success style of the `character` module.
+fn continuation_prompt<'a>(context: &'a Context) -> Module<'a> {
+    let mut module = context.new_module("continuation_prompt");
+    let config: ContinuationPromptConfig = module.load_config();
+
+    let module_style = config.style.unwrap_or_else(|| {
+        let character_config = context.config.get_module_config("character");
+        CharacterConfig::load("character", character_config).style_success
+    });
+    module.set_style(module_style);
+    module.get_prefix().set_value("");
+
+    module.new_segment("symbol", &config.symbol);
+
+    module
+}
+
+/// Read a list of module names from the given config key. Returns `None` if the
+/// key is missing or holds an empty list.
+fn get_prompt_order<'a>(config: &'a toml::value::Table, key: &str) -> Option<Vec<&'a str>> {
+    let modules = config.get_as_array(key)?;
+    if modules.is_empty() {
+        return None;
     }
 
-    // Print all remaining modules
-    printable.for_each(|module| write!(handle, "{}", module).unwrap());
+    let mut prompt_order: Vec<&str> = Vec::new();
+    for module in modules {
+        let str_value = module.as_str();
+
+        if let Some(value) = str_value {
+            if ALL_MODULES.contains(&value)
+                || value == "custom"
+                || (value.starts_with("custom.") && config.get_module_config(value).is_some())
+            {
+                prompt_order.push(value);
+            } else {
+                log::debug!(
+                    "Expected {} to contain value from {:?}. Instead received {}",
+                    key,
+                    ALL_MODULES,
+                    value,
+                );
+            }
+        } else {
+            log::debug!(
+                "Expected {} to be an array of strings. Instead received {} of type {}",
+                key,
+                module,
+                module.type_str()
+            );
+        }
+    }
+
+    Some(prompt_order)
 }
 
-pub fn module(module_name: &str, args: ArgMatches) {
-    let context = Context::new(args);
+pub fn module(module_name: &str, args: ArgMatches<'static>) {
+    let context = leak_context(Context::new(args));
+
+    if is_json_output(context) {
+        let modules = compute_modules(context, &[module_name.to_string()]);
+        println!("{}", modules_to_json(&modules));
+        return;
+    }
 
     // If the module returns `None`, print an empty string
-    let module = modules::handle(module_name, &context)
-        .map(|m| m.to_string())
+    let module = compute_modules(context, &[module_name.to_string()])
+        .pop()
+        .map(|m| m.render(output_backend(context)))
         .unwrap_or_default();
 
     print!("{}", module);
 }
+
+fn is_json_output(context: &Context) -> bool {
+    context.arguments.value_of("output") == Some("json")
+}
+
+/// The backend rendering the prompt. JSON output is handled separately, as it
+/// describes the modules rather than rendering them.
+fn output_backend(context: &Context) -> Output {
+    context
+        .arguments
+        .value_of("output")
+        .and_then(Output::from_name)
+        .unwrap_or(Output::Ansi)
+}
+
+/// Describes the modules as a JSON array, leaving the rendering to the caller
+fn modules_to_json(modules: &[Module]) -> serde_json::Value {
+    serde_json::Value::Array(modules.iter().map(Module::to_json).collect())
+}
+
+/// The context lives for the rest of the process, so that modules exceeding
+/// `render_timeout` can be abandoned while they are still running.
+fn leak_context(context: Context<'static>) -> &'static Context<'static> {
+    Box::leak(Box::new(context))
+}
+
+/// Computes the given modules in parallel, leaving out modules which return `None`.
+/// If `render_timeout` is set, modules which haven't finished computing by then
+/// are dropped, rather than holding up the prompt.
+pub fn compute_modules(
+    context: &'static Context<'static>,
+    module_list: &[String],
+) -> Vec<Module<'static>> {
+    let render_timeout = match context.config.get_as_i64("render_timeout") {
+        Some(millis) if millis >= 0 => Duration::from_millis(millis as u64),
+        _ => {
+            return module_list
+                .par_iter()
+                .map(|module| modules::handle(module, context)) // Compute modules
+                .flatten() // Remove segments set to `None`
+                .collect::<Vec<Module>>();
+        }
+    };
+
+    // Each module gets its own thread, so that a hanging module can't hold up the
+    // others by occupying a thread of a shared pool
+    let deadline = Instant::now() + render_timeout;
+    let (sender, receiver) = mpsc::channel();
+    for (index, module) in module_list.iter().enumerate() {
+        let sender = sender.clone();
+        let module = module.clone();
+        thread::spawn(move || {
+            let computed = modules::handle(&module, context);
+            sender.send((index, computed)).ok();
+        });
+    }
+
+    let mut computed_modules = module_list.iter().map(|_| None).collect::<Vec<_>>();
+    for _ in module_list {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok((index, computed)) => computed_modules[index] = Some(computed),
+            Err(_) => break,
+        }
+    }
+
+    module_list
+        .iter()
+        .zip(computed_modules)
+        .filter_map(|(module, computed)| {
+            if computed.is_none() {
+                log::warn!(
+                    "Module \"{}\" was dropped for exceeding render_timeout ({}ms)",
+                    module,
+                    render_timeout.as_millis()
+                );
+            }
+            computed.flatten()
+        })
+        .collect()
+}
@@ -1,12 +1,15 @@
 use ansi_term::{ANSIString, Style};
+use serde_json::json;
 use std::fmt;
 
+use crate::config::style_to_string;
+
 /// A segment is a single configurable element in a module. This will usually
 /// contain a data point to provide context for the prompt's user
 /// (e.g. The version that software is running).
 pub struct Segment {
     /// The segment's name, to be used in configuration and logging.
-    _name: String,
+    name: String,
 
     /// The segment's style. If None, will inherit the style of the module containing it.
     style: Option<Style>,
@@ -19,12 +22,22 @@ impl Segment {
     /// Creates a new segment with default fields.
     pub fn new(name: &str) -> Self {
         Self {
-            _name: name.to_string(),
+            name: name.to_string(),
             style: None,
             value: "".to_string(),
         }
     }
 
+    /// Gets the name of the segment.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the value of the segment.
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
     /// Sets the style of the segment.
     ///
     /// Accepts either `Color` or `Style`.
@@ -53,6 +66,16 @@ impl Segment {
         }
     }
 
+    /// Describes the segment as JSON, with its name, value and style string.
+    /// A segment without a style of its own has an empty style string.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "value": self.value,
+            "style": self.style.map(|style| style_to_string(&style)).unwrap_or_default(),
+        })
+    }
+
     /// Determines if the segment contains a value.
     pub fn is_empty(&self) -> bool {
         self.value.trim().is_empty()
@@ -1,6 +1,13 @@
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{Read, Result};
 use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 /// Return the string contents of a file
 pub fn read_file<P: AsRef<Path>>(file_name: P) -> Result<String> {
@@ -10,3 +17,216 @@ pub fn read_file<P: AsRef<Path>>(file_name: P) -> Result<String> {
     file.read_to_string(&mut data)?;
     Ok(data)
 }
+
+/// The number of terminal columns taken up by the text. Each grapheme cluster,
+/// such as an emoji joined from several characters, is at most two columns wide.
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true)
+        .map(|grapheme| grapheme.width().min(2))
+        .sum()
+}
+
+/// Whether the text matches a glob pattern, in which `*` matches any number of
+/// characters and `?` matches a single character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // The position of the last `*`, and of the text it was last matched up to
+    let mut star = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more character
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The output of an executed command
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+
+    /// Whether the command exited successfully
+    pub success: bool,
+}
+
+/// Execute a command and return its output. If the command hasn't exited once
+/// `timeout` has elapsed, it is killed and `None` is returned.
+pub fn exec_cmd(cmd: &str, args: &[&str], timeout: Duration) -> Option<CommandOutput> {
+    log::trace!("Executing command {:?} with args {:?}", cmd, args);
+    let start = Instant::now();
+
+    let mut child = match Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(error) => {
+            log::debug!("Unable to run {:?}: {}", cmd, error);
+            return None;
+        }
+    };
+
+    // The pipes are read in the background so that a command with a lot of
+    // output can't block on a full pipe while we wait for it to exit
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let remaining = || timeout.checked_sub(start.elapsed()).unwrap_or_default();
+    let output = stdout
+        .recv_timeout(remaining())
+        .and_then(|stdout| Ok((stdout, stderr.recv_timeout(remaining())?)));
+    let (stdout, stderr) = match output {
+        Ok(output) => output,
+        Err(_) => return kill_timed_out(cmd, child, timeout),
+    };
+
+    // A command can close its pipes, or hand them to a detached process, and keep
+    // running, so its exit is waited for within the timeout as well
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if remaining() > Duration::from_millis(0) => {
+                thread::sleep(remaining().min(Duration::from_millis(1)))
+            }
+            Ok(None) => return kill_timed_out(cmd, child, timeout),
+            Err(error) => {
+                log::debug!("Unable to wait for {:?}: {}", cmd, error);
+                return None;
+            }
+        }
+    };
+
+    log::trace!("Command {:?} took {}ms", cmd, start.elapsed().as_millis());
+    Some(CommandOutput {
+        stdout,
+        stderr,
+        success: status.success(),
+    })
+}
+
+/// Kill a command which hasn't exited within its timeout
+fn kill_timed_out(cmd: &str, mut child: Child, timeout: Duration) -> Option<CommandOutput> {
+    log::warn!(
+        "Executing command {:?} timed out after {}ms",
+        cmd,
+        timeout.as_millis()
+    );
+    child.kill().ok();
+    child.wait().ok();
+    None
+}
+
+/// Read a pipe to the end on another thread, sending its contents once it's closed
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut data = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut data).ok();
+        }
+        sender
+            .send(String::from_utf8_lossy(&data).into_owned())
+            .ok();
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_width_counts_columns() {
+        assert_eq!(display_width("main"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("🦀 v1.40"), 8);
+        assert_eq!(display_width("👨\u{200d}👩\u{200d}👧"), 2);
+        assert_eq!(display_width("日本"), 4);
+    }
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("build-*", "build-04"));
+        assert!(glob_match("*.example.com", "ci.example.com"));
+        assert!(glob_match("web-??", "web-12"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("web-??", "web-123"));
+        assert!(!glob_match("build-*", "laptop"));
+        assert!(!glob_match("a*b", "acbc"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn exec_cmd_returns_output() {
+        let output = exec_cmd("echo", &["hello"], Duration::from_millis(1000));
+        let expected = Some(CommandOutput {
+            stdout: String::from("hello\n"),
+            stderr: String::new(),
+            success: true,
+        });
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn exec_cmd_reports_failure() {
+        let output = exec_cmd("false", &[], Duration::from_millis(1000));
+        assert_eq!(output.map(|output| output.success), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn exec_cmd_times_out() {
+        let output = exec_cmd("sleep", &["5"], Duration::from_millis(50));
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn exec_cmd_times_out_when_output_is_held_open() {
+        // The background `sleep` keeps the pipes open after the shell has exited
+        let start = Instant::now();
+        let output = exec_cmd(
+            "sh",
+            &["-c", "sleep 5 & echo started"],
+            Duration::from_millis(50),
+        );
+        assert_eq!(output, None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn exec_cmd_missing_command() {
+        let output = exec_cmd(
+            "a_command_which_does_not_exist",
+            &[],
+            Duration::from_millis(50),
+        );
+        assert_eq!(output, None);
+    }
+}
@@ -58,3 +58,41 @@ fn config_5s_duration_10s() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn config_display_thresholds() -> io::Result<()> {
+    let output = common::render_module("cmd_duration")
+        .use_config(toml::toml! {
+            [[cmd_duration.display]]
+            threshold = 30
+            style = "bold red"
+
+            [[cmd_duration.display]]
+            threshold = 10
+            style = "purple"
+        })
+        .arg("--cmd-duration=45")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.bold().paint("took 45s"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn config_display_below_thresholds() -> io::Result<()> {
+    let output = common::render_module("cmd_duration")
+        .use_config(toml::toml! {
+            [[cmd_duration.display]]
+            threshold = 30
+            style = "bold red"
+        })
+        .arg("--cmd-duration=5")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Yellow.bold().paint("took 5s"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
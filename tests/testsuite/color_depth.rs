@@ -0,0 +1,53 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+fn render_jobs(color_depth: &str, term: &str) -> io::Result<String> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("TERM", term)
+        .use_config(toml::toml! {
+            color_depth = color_depth
+            [jobs]
+            style = "#ff8700"
+        })
+        .output()?;
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn truecolor_terminal_keeps_rgb_colors() -> io::Result<()> {
+    let actual = render_jobs("auto", "xterm-direct")?;
+
+    let expected = format!("{} ", Color::RGB(255, 135, 0).paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn colors_downgraded_for_256_color_terminal() -> io::Result<()> {
+    let actual = render_jobs("auto", "xterm-256color")?;
+
+    let expected = format!("{} ", Color::Fixed(208).paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn colors_downgraded_for_linux_console() -> io::Result<()> {
+    let actual = render_jobs("auto", "linux")?;
+
+    let expected = format!("{} ", Color::Yellow.paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn color_depth_overrides_detection() -> io::Result<()> {
+    let actual = render_jobs("256", "linux")?;
+
+    let expected = format!("{} ", Color::Fixed(208).paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
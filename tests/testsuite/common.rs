@@ -36,6 +36,20 @@ pub fn render_module(module_name: &str) -> process::Command {
     command
 }
 
+/// Run starship with the given arguments. The binary is found from the manifest
+/// directory, so that tests may change the working directory.
+pub fn starship_command(args: &[&str]) -> process::Command {
+    let mut command = process::Command::new(MANIFEST_DIR.join("target/debug/starship"));
+
+    command
+        .args(args)
+        .env_clear()
+        .env("PATH", env!("PATH")) // Provide the $PATH variable so that external programs are runnable
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
 /// Create a temporary directory with full access permissions (rwxrwxrwt).
 pub fn new_tempdir() -> io::Result<tempfile::TempDir> {
     //  Using `tempfile::TempDir` directly creates files on macOS within
@@ -0,0 +1,159 @@
+use std::fs;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn valid_config() -> io::Result<()> {
+    let output = common::starship_command(&["config", "check"])
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["directory", "character"]
+
+            [directory]
+            truncation_length = 2
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(actual.ends_with(": no problems found\n"));
+    Ok(())
+}
+
+#[test]
+fn segment_overrides_are_valid() -> io::Result<()> {
+    let output = common::starship_command(&["config", "check"])
+        .use_config(toml::toml! {
+            [git_branch]
+            name = "main"
+
+            [line_break]
+            character = ""
+
+            [time]
+            time = "now"
+
+            [custom.greeting]
+            command = "echo hello"
+            output = "hi"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(actual.ends_with(": no problems found\n"));
+    Ok(())
+}
+
+#[test]
+fn invalid_config() -> io::Result<()> {
+    let output = common::starship_command(&["config", "check"])
+        .use_config(toml::toml! {
+            [directory]
+            truncation_length = "2"
+            style = "bold cyna"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual
+        .contains(": invalid value for \"style\" in [directory]: invalid style \"bold cyna\": unknown color \"cyna\"\n"));
+    assert!(actual.contains(
+        ": invalid value for \"truncation_length\" in [directory]: \
+         invalid type: string \"2\", expected i64\n"
+    ));
+    assert!(actual.ends_with("Found 2 problem(s)\n"));
+    Ok(())
+}
+
+#[test]
+fn syntax_error() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, "add_newline = false\n[directory\n")?;
+
+    let output = common::starship_command(&["config", "check"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    let expected = format!("{}:2:", config_path.display());
+    assert!(actual.starts_with(&expected));
+    assert!(actual.contains("syntax error"));
+    Ok(())
+}
+
+#[test]
+fn checks_directory_configs() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let directory_config = project_dir.path().join(".starship.toml");
+    fs::write(
+        &directory_config,
+        "[jobs]\nthreshold = \"2\"\n\n[custom.foo]\ncommand = \"echo foo\"\n",
+    )?;
+
+    let output = common::starship_command(&["config", "check"])
+        .current_dir(project_dir.path())
+        .use_config(toml::toml! {
+            add_newline = false
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains(": no problems found\n"));
+    assert!(actual.contains(&format!(
+        "{}: custom modules are ignored in per-directory files\n",
+        directory_config.display()
+    )));
+    assert!(actual.ends_with("Found 2 problem(s)\n"));
+    Ok(())
+}
+
+#[test]
+fn checks_included_files() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    let theme_path = dir.path().join("theme.toml");
+    fs::write(
+        &config_path,
+        "include = [\"theme.toml\", \"missing.toml\"]\n",
+    )?;
+    fs::write(&theme_path, "[jobs]\nthreshold = 2\n")?;
+
+    let output = common::starship_command(&["config", "check"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains(&format!(
+        "{}: unable to read the configuration file: ",
+        dir.path().join("missing.toml").display()
+    )));
+    assert!(actual.ends_with("Found 1 problem(s)\n"));
+    Ok(())
+}
+
+#[test]
+fn reports_include_cycles() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, "include = [\"theme.toml\"]\n")?;
+    fs::write(
+        dir.path().join("theme.toml"),
+        "include = [\"starship.toml\"]\n",
+    )?;
+
+    let output = common::starship_command(&["config", "check"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains(": include cycle: "));
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::io;
+
+use crate::common;
+
+const CONFIG: &str = "# My prompt
+[git_branch]
+style = \"bold purple\"  # the branch
+";
+
+#[test]
+fn set_keeps_comments() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, CONFIG)?;
+
+    let output = common::starship_command(&["config", "set", "git_branch.style", "bold red"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    assert!(output.status.success());
+    let output = common::starship_command(&["config", "set", "directory.truncation_length", "5"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    assert!(output.status.success());
+
+    let expected = "# My prompt
+[git_branch]
+style = \"bold red\"  # the branch
+
+[directory]
+truncation_length = 5
+";
+    assert_eq!(expected, fs::read_to_string(&config_path)?);
+    Ok(())
+}
+
+#[test]
+fn get_prints_option() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, CONFIG)?;
+
+    let output = common::starship_command(&["config", "get", "git_branch.style"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!("bold purple\n", String::from_utf8(output.stdout).unwrap());
+
+    let output = common::starship_command(&["config", "get", "git_branch.symbol"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    assert!(!output.status.success());
+    assert_eq!(
+        "\"git_branch.symbol\" is not set\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+    Ok(())
+}
+
+#[test]
+fn unset_removes_option() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, CONFIG)?;
+
+    let output = common::starship_command(&["config", "unset", "git_branch.style"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        "# My prompt\n[git_branch]\n",
+        fs::read_to_string(&config_path)?
+    );
+    Ok(())
+}
+
+#[test]
+fn edit_opens_editor() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let config_path = dir.path().join("starship").join("starship.toml");
+
+    let output = common::starship_command(&["config", "edit"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .env("EDITOR", "touch")
+        .output()?;
+    assert!(output.status.success());
+    assert!(config_path.is_file());
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use ansi_term::Color;
+use std::fs::{self, File};
 use std::io;
+use std::time::{Duration, Instant};
 
 use crate::common::{self, TestCommand};
 
@@ -39,3 +41,182 @@ fn add_newline_configuration() -> io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn format_configuration() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            [aws]
+            format = "[$profile](bold red) \\[$symbol\\] "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "{} [{}] ",
+        Color::Red.bold().paint("astronauts"),
+        Color::Yellow.bold().paint("☁️ ")
+    );
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn format_configuration_hides_empty_groups() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            [aws]
+            format = "[$profile]($style)[ ($region)](red) "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.bold().paint("astronauts"));
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn right_prompt_configuration() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--right")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            right_prompt_order = ["aws"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.bold().paint("☁️ astronauts"));
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn right_prompt_modules_left_out_of_default_prompt_order() -> io::Result<()> {
+    let output = common::render_prompt()
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            right_prompt_order = ["aws"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(!actual.contains("astronauts"));
+
+    Ok(())
+}
+
+#[test]
+fn continuation_prompt_configuration() -> io::Result<()> {
+    // By default, the continuation prompt reuses the style of the character module
+    let output = common::render_prompt()
+        .arg("--continuation")
+        .use_config(toml::toml! {
+            [character]
+            style_success = "bold blue"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Blue.bold().paint("∙"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_prompt()
+        .arg("--continuation")
+        .use_config(toml::toml! {
+            [continuation_prompt]
+            symbol = "…"
+            style = "bold yellow"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.bold().paint("…"));
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+/// Create a Java project whose `java` binary hangs for five seconds
+#[cfg(not(windows))]
+fn create_hanging_java_project(script: &str) -> io::Result<(tempfile::TempDir, tempfile::TempDir)> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let project_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("Main.java"))?;
+
+    let java_home = common::new_tempdir()?;
+    let java_bin = java_home.path().join("bin");
+    fs::create_dir(&java_bin)?;
+    let java = java_bin.join("java");
+    fs::write(&java, format!("#!/bin/sh\n{}\n", script))?;
+    fs::set_permissions(&java, fs::Permissions::from_mode(0o755))?;
+
+    Ok((project_dir, java_home))
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_timeout_configuration() -> io::Result<()> {
+    let (project_dir, java_home) = create_hanging_java_project("sleep 5")?;
+
+    let start = Instant::now();
+    let output = common::render_module("java")
+        .arg("--path")
+        .arg(project_dir.path())
+        .env("JAVA_HOME", java_home.path())
+        .use_config(toml::toml! {
+            command_timeout = 100
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_timeout_after_output_closed() -> io::Result<()> {
+    let (project_dir, java_home) = create_hanging_java_project("exec >&- 2>&-; sleep 5")?;
+
+    let start = Instant::now();
+    let output = common::render_module("java")
+        .arg("--path")
+        .arg(project_dir.path())
+        .env("JAVA_HOME", java_home.path())
+        .use_config(toml::toml! {
+            command_timeout = 100
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(windows))]
+fn render_timeout_configuration() -> io::Result<()> {
+    let (project_dir, java_home) = create_hanging_java_project("sleep 5")?;
+
+    let start = Instant::now();
+    let output = common::render_prompt()
+        .arg("--path")
+        .arg(project_dir.path())
+        .env("JAVA_HOME", java_home.path())
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["java", "line_break"]
+            command_timeout = 10000
+            render_timeout = 100
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("\n", actual);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
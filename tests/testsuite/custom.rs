@@ -0,0 +1,169 @@
+use ansi_term::Color;
+use std::fs::File;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn command_output() -> io::Result<()> {
+    let output = common::render_module("custom.greeting")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "echo hello"
+            symbol = "👋 "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("with {} ", Color::Green.bold().paint("👋 hello"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn configured_style() -> io::Result<()> {
+    let output = common::render_module("custom.greeting")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "echo hello"
+            style = "red"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("with {} ", Color::Red.paint("hello"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn empty_output() -> io::Result<()> {
+    let output = common::render_module("custom.greeting")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "true"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("", actual);
+    Ok(())
+}
+
+#[test]
+fn blank_output_hides_symbol() -> io::Result<()> {
+    let output = common::render_module("custom.greeting")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "echo '  '"
+            symbol = "👋 "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("", actual);
+    Ok(())
+}
+
+#[test]
+fn when_condition() -> io::Result<()> {
+    let output = common::render_module("custom.greeting")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "echo hello"
+            when = "test -n \"$GREET\""
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+
+    let output = common::render_module("custom.greeting")
+        .env("GREET", "1")
+        .use_config(toml::toml! {
+            [custom.greeting]
+            command = "echo hello"
+            when = "test -n \"$GREET\""
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("with {} ", Color::Green.bold().paint("hello"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn detected_files() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+
+    let output = common::render_module("custom.deploy")
+        .arg("--path")
+        .arg(dir.path())
+        .use_config(toml::toml! {
+            [custom.deploy]
+            command = "echo staging"
+            files = ["deploy.yml"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+
+    File::create(dir.path().join("deploy.yml"))?;
+    let output = common::render_module("custom.deploy")
+        .arg("--path")
+        .arg(dir.path())
+        .use_config(toml::toml! {
+            [custom.deploy]
+            command = "echo staging"
+            files = ["deploy.yml"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("with {} ", Color::Green.bold().paint("staging"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn configured_shell() -> io::Result<()> {
+    let output = common::render_module("custom.shell")
+        .use_config(toml::toml! {
+            [custom.shell]
+            command = "echo $0"
+            shell = ["sh"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("with {} ", Color::Green.bold().paint("sh"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn custom_modules_in_prompt_order() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["custom.second", "line_break", "custom"]
+
+            [custom.first]
+            command = "echo one"
+
+            [custom.second]
+            command = "echo two"
+
+            [custom.third]
+            command = "echo three"
+            disabled = true
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "{} \nwith {} ",
+        Color::Green.bold().paint("two"),
+        Color::Green.bold().paint("one")
+    );
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn explain_describes_visible_modules() -> io::Result<()> {
+    let output = common::starship_command(&["explain", "--jobs=3"])
+        .use_config(toml::toml! {
+            prompt_order = ["jobs", "cmd_duration", "character"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "\
+jobs: The jobs running in the background
+  \"✦\"  symbol: there are jobs running in the background
+  \"3\"  number: the number of jobs running in the background
+character: Where the text input starts, showing whether the last command succeeded
+  \"❯\"  symbol: the last command succeeded
+";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn explain_describes_failed_command() -> io::Result<()> {
+    let output = common::starship_command(&["explain", "--status=1"])
+        .use_config(toml::toml! {
+            prompt_order = ["character"]
+
+            [character]
+            use_symbol_for_status = true
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("error_symbol: the last command failed"));
+    Ok(())
+}
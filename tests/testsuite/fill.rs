@@ -0,0 +1,70 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn fill_pads_line_to_terminal_width() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--jobs=2")
+        .arg("--output=plain")
+        .arg("--terminal-width=12")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["jobs", "fill", "jobs", "line_break", "character"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("✦2 ......✦2 \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn fill_shares_space_and_pads_wide_symbols() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--jobs=2")
+        .arg("--output=plain")
+        .arg("--terminal-width=12")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["fill", "jobs", "fill", "character"]
+
+            [fill]
+            symbol = "日"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("日日✦2 日 ❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn fill_is_styled() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--terminal-width=5")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["fill", "character"]
+
+            [fill]
+            symbol = "-"
+            style = "red"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.starts_with(&ansi_term::Color::Red.paint("---").to_string()));
+    Ok(())
+}
+
+#[test]
+fn fill_without_terminal_width_shows_symbol() -> io::Result<()> {
+    let output = common::render_module("fill")
+        .arg("--output=plain")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(".", actual);
+    Ok(())
+}
@@ -295,6 +295,31 @@ fn shows_modified() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+#[ignore]
+fn display_style_for_changed_files() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    File::create(repo_dir.join("readme.md"))?;
+    File::create(repo_dir.join("untracked.txt"))?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [[git_status.display]]
+            threshold = 2
+            style = "yellow"
+        })
+        .arg("--path")
+        .arg(repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Yellow.paint(format!("[{}] ", "!?")).to_string();
+
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
 #[test]
 #[ignore]
 fn shows_staged_file() -> io::Result<()> {
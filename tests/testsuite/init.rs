@@ -0,0 +1,35 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn right_prompt_not_installed_by_default() -> io::Result<()> {
+    let output = common::starship_command(&["init", "zsh", "--print-full-init"]).output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("STARSHIP_RIGHT_PROMPT=0"));
+
+    let output = common::starship_command(&["init", "fish", "--print-full-init"]).output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("if test 0 = 1"));
+    Ok(())
+}
+
+#[test]
+fn right_prompt_installed_when_configured() -> io::Result<()> {
+    let output = common::starship_command(&["init", "zsh", "--print-full-init"])
+        .use_config(toml::toml! {
+            right_prompt_order = ["time"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("STARSHIP_RIGHT_PROMPT=1"));
+
+    let output = common::starship_command(&["init", "fish", "--print-full-init"])
+        .use_config(toml::toml! {
+            right_prompt_order = ["time"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("if test 1 = 1"));
+    Ok(())
+}
@@ -64,3 +64,20 @@ fn config_2_job_3() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn config_display_thresholds() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .use_config(toml::toml! {
+            [[jobs.display]]
+            threshold = 3
+            style = "bold red"
+        })
+        .arg("--jobs=4")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.bold().paint("✦4"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use serde_json::{json, Value};
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn prompt_json_lists_modules() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--jobs=3")
+        .arg("--output=json")
+        .use_config(toml::toml! {
+            prompt_order = ["jobs", "character"]
+        })
+        .output()?;
+    let actual: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let expected = json!([
+        {
+            "name": "jobs",
+            "prefix": { "value": "", "style": "" },
+            "segments": [
+                { "name": "symbol", "value": "✦", "style": "bold fg:blue" },
+                { "name": "number", "value": "3", "style": "bold fg:blue" },
+            ],
+            "suffix": { "value": " ", "style": "" },
+        },
+        {
+            "name": "character",
+            "prefix": { "value": "", "style": "" },
+            "segments": [
+                { "name": "symbol", "value": "❯", "style": "bold fg:green" },
+            ],
+            "suffix": { "value": " ", "style": "" },
+        },
+    ]);
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn module_json_is_empty_when_module_is_hidden() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=0")
+        .arg("--output=json")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("[]\n", actual);
+    Ok(())
+}
+
+#[test]
+fn module_json_describes_module() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=2")
+        .arg("--output=json")
+        .output()?;
+    let actual: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(actual[0]["name"], "jobs");
+    assert_eq!(actual[0]["segments"][1]["value"], "2");
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use ansi_term::Color;
+use std::fs;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn system_config_is_overridden_by_user_config() -> io::Result<()> {
+    let system_dir = common::new_tempdir()?;
+    fs::write(
+        system_dir.path().join("starship.toml"),
+        "[jobs]\nsymbol = \"+\"\nstyle = \"bold red\"\n",
+    )?;
+
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("XDG_CONFIG_DIRS", system_dir.path())
+        .use_config(toml::toml! {
+            [jobs]
+            style = "green"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.paint("+"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn directory_configs_override_parent_directories() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let sub_dir = project_dir.path().join("sub");
+    fs::create_dir(&sub_dir)?;
+    fs::write(
+        project_dir.path().join(".starship.toml"),
+        "[jobs]\nsymbol = \"+\"\nstyle = \"bold red\"\n",
+    )?;
+    fs::write(
+        sub_dir.join(".starship.toml"),
+        "[jobs]\nstyle = \"green\"\n",
+    )?;
+
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .arg("--path")
+        .arg(&sub_dir)
+        .use_config(toml::toml! {
+            [jobs]
+            symbol = "*"
+            style = "purple"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.paint("+"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn directory_config_can_disable_modules() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    fs::write(
+        project_dir.path().join(".starship.toml"),
+        "[jobs]\ndisabled = true\n",
+    )?;
+
+    let output = common::render_prompt()
+        .arg("--jobs=1")
+        .arg("--path")
+        .arg(project_dir.path())
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["jobs", "character"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn directory_config_cannot_add_custom_modules() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    fs::write(
+        project_dir.path().join(".starship.toml"),
+        "[custom.test]\ncommand = \"echo pwned\"\nwhen = \"true\"\n",
+    )?;
+
+    let output = common::render_module("custom.test")
+        .arg("--path")
+        .arg(project_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!actual.contains("pwned"));
+    Ok(())
+}
+
+#[test]
+fn included_files_are_merged() -> io::Result<()> {
+    let home_dir = common::new_tempdir()?;
+    let config_dir = home_dir.path().join(".config");
+    fs::create_dir(&config_dir)?;
+    fs::write(
+        home_dir.path().join("theme.toml"),
+        "[jobs]\nsymbol = \"+\"\nstyle = \"bold red\"\n",
+    )?;
+    fs::write(
+        config_dir.join("starship.toml"),
+        "include = [\"~/theme.toml\"]\n\n[jobs]\nstyle = \"green\"\n",
+    )?;
+
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("HOME", home_dir.path())
+        .env_remove("STARSHIP_CONFIG")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.paint("+"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
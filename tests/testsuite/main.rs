@@ -1,22 +1,39 @@
 mod aws;
 mod character;
 mod cmd_duration;
+mod color_depth;
 mod common;
+mod config_check;
+mod config_edit;
 mod configuration;
+mod custom;
 mod directory;
 mod env_var;
+mod explain;
+mod fill;
 mod git_branch;
 mod git_state;
 mod git_status;
 mod golang;
 mod hostname;
+mod init;
 mod java;
 mod jobs;
+mod json_output;
+mod layered_config;
 mod line_break;
 mod modules;
 mod nix_shell;
 mod nodejs;
+mod output;
+mod palette;
+mod powerline;
+mod print_config;
+mod profiles;
 mod python;
 mod ruby;
+mod terminal_width;
 mod time;
+mod timings;
 mod username;
+mod version_cache;
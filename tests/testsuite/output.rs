@@ -0,0 +1,97 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn plain_output_has_no_styling() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=2")
+        .arg("--output=plain")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("✦2 ", actual);
+    Ok(())
+}
+
+#[test]
+fn tmux_output_uses_style_sequences() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=2")
+        .arg("--output=tmux")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "#[fg=blue,bold]✦#[default]#[fg=blue,bold]2#[default] ";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn zsh_output_uses_prompt_sequences() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=2")
+        .arg("--output=zsh")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "%F{blue}%B✦%f%b%F{blue}%B2%f%b ";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn ansi_output_is_escaped_for_bash() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("STARSHIP_SHELL", "bash")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "\\[\u{1b}[1;34m\\]✦\\[\u{1b}[0m\\] ";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn no_color_flag_drops_styling() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=2")
+        .arg("--no-color")
+        .env("STARSHIP_SHELL", "bash")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("✦2 ", actual);
+    Ok(())
+}
+
+#[test]
+fn no_color_env_var_drops_styling() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--jobs=2")
+        .env("NO_COLOR", "1")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["jobs", "character"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("✦2 ❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn empty_no_color_env_var_is_ignored() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("NO_COLOR", "")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Blue.bold().paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
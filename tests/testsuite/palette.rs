@@ -0,0 +1,78 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+fn config(palette: &str) -> toml::value::Value {
+    toml::toml! {
+        palette = palette
+
+        [palettes.light]
+        accent = "#c792ea"
+
+        [palettes.dark]
+        accent = "21"
+        blue = "#0000aa"
+
+        [jobs]
+        style = "bold accent"
+    }
+}
+
+#[test]
+fn style_uses_palette_color() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .use_config(config("light"))
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::RGB(0xc7, 0x92, 0xea).bold().paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn palette_is_swapped_in_one_line() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .use_config(config("dark"))
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Fixed(21).bold().paint("✦"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn palette_overrides_predefined_colors_in_formats() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .use_config(toml::toml! {
+            palette = "dark"
+
+            [palettes.dark]
+            blue = "#0000aa"
+
+            [jobs]
+            format = "[$symbol](blue)"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::RGB(0, 0, 0xaa).paint("✦").to_string();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn config_check_accepts_palette_colors() -> io::Result<()> {
+    let output = common::starship_command(&["config", "check"])
+        .use_config(config("light"))
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{}", actual);
+    Ok(())
+}
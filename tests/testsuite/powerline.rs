@@ -0,0 +1,72 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+fn config() -> toml::value::Value {
+    toml::toml! {
+        add_newline = false
+        prompt_order = ["cmd_duration", "jobs", "line_break", "character"]
+        right_prompt_order = ["cmd_duration", "jobs"]
+
+        [powerline]
+        disabled = false
+        separator = ">"
+        right_separator = "<"
+
+        [jobs]
+        style = "fg:white bg:blue"
+    }
+}
+
+#[test]
+fn powerline_separators_blend_backgrounds() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--cmd-duration=100")
+        .arg("--jobs=2")
+        .arg("--output=tmux")
+        .use_config(config())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "\
+#[fg=black,bg=yellow]took 1m40s#[default]#[fg=black,bg=yellow] #[default]\
+#[fg=yellow,bg=blue]>#[default]\
+#[fg=white,bg=blue]✦#[default]#[fg=white,bg=blue]2#[default]#[bg=blue] #[default]\
+#[fg=blue]>#[default]\n\
+#[fg=green,bold]❯#[default] ";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn powerline_right_prompt_separators_point_left() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--right")
+        .arg("--cmd-duration=100")
+        .arg("--jobs=2")
+        .arg("--output=tmux")
+        .use_config(config())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = "\
+#[fg=yellow]<#[default]\
+#[fg=black,bg=yellow]took 1m40s#[default]#[fg=black,bg=yellow] #[default]\
+#[fg=blue,bg=yellow]<#[default]\
+#[fg=white,bg=blue]✦#[default]#[fg=white,bg=blue]2#[default]#[bg=blue] #[default]";
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn powerline_is_disabled_without_color() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--jobs=2")
+        .arg("--no-color")
+        .use_config(config())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("✦2 \n❯ ", actual);
+    Ok(())
+}
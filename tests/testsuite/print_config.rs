@@ -0,0 +1,86 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn module_defaults_with_options_set() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let output = common::starship_command(&["print-config", "directory"])
+        .current_dir(dir.path())
+        .use_config(toml::toml! {
+            [directory]
+            truncation_length = 5
+            style = "bold cyna"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    // The invalid style is ignored in favor of its default
+    let expected = "[directory]
+disabled = false
+fish_style_pwd_dir_length = 0
+style = \"bold fg:cyan\"
+truncate_to_repo = true
+truncation_length = 5
+use_logical_path = true
+";
+    assert!(output.status.success());
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn whole_config_with_profiles_applied() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let output = common::starship_command(&["print-config"])
+        .current_dir(dir.path())
+        .env("STARSHIP_PROFILE", "minimal")
+        .use_config(toml::toml! {
+            [profiles.minimal]
+            add_newline = false
+
+            [custom.foo]
+            command = "echo foo"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(actual.starts_with("add_newline = false\ncolor_depth = \"auto\"\n"));
+    assert!(actual.contains("\n[custom.foo]\ncommand = \"echo foo\"\n"));
+    assert!(actual.contains("\n[git_branch]\n"));
+    assert!(!actual.contains("profiles"));
+    Ok(())
+}
+
+#[test]
+fn invalid_root_options_left_out() -> io::Result<()> {
+    let dir = common::new_tempdir()?;
+    let output = common::starship_command(&["print-config"])
+        .current_dir(dir.path())
+        .use_config(toml::toml! {
+            add_newlin = false
+            command_timeout = 100
+            scan_timeout = "fast"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(actual.contains("\ncommand_timeout = 100\n"));
+    assert!(!actual.contains("add_newlin ="));
+    assert!(!actual.contains("\"fast\""));
+    Ok(())
+}
+
+#[test]
+fn unknown_module() -> io::Result<()> {
+    let output = common::starship_command(&["print-config", "dirctory"]).output()?;
+
+    assert!(!output.status.success());
+    assert_eq!(
+        "Unknown module \"dirctory\"\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn profile_selected_by_env_var() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("STARSHIP_PROFILE", "minimal")
+        .use_config(toml::toml! {
+            [jobs]
+            symbol = "+"
+
+            [profiles.minimal.jobs]
+            symbol = "*"
+            style = "green"
+
+            [profiles.other.jobs]
+            symbol = "-"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.paint("*"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn profile_matching_ssh_session() -> io::Result<()> {
+    let config = toml::toml! {
+        [jobs]
+        symbol = "+"
+        style = "green"
+
+        [profiles.remote]
+        when = { ssh = true }
+        [profiles.remote.jobs]
+        style = "red"
+    };
+
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .env("SSH_CONNECTION", "192.168.0.1 52000 192.168.0.2 22")
+        .use_config(config.clone())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(format!("{} ", Color::Red.paint("+")), actual);
+
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .use_config(config)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(format!("{} ", Color::Green.paint("+")), actual);
+    Ok(())
+}
+
+#[test]
+fn profile_matching_hostname() -> io::Result<()> {
+    let output = common::render_module("jobs")
+        .arg("--jobs=1")
+        .use_config(toml::toml! {
+            [jobs]
+            symbol = "+"
+            style = "green"
+
+            [profiles.anywhere]
+            when = { hostname = "*" }
+            [profiles.anywhere.jobs]
+            style = "red"
+
+            [profiles.nowhere]
+            when = { hostname = "no-such-host-*" }
+            [profiles.nowhere.jobs]
+            symbol = "-"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.paint("+"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
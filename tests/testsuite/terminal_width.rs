@@ -0,0 +1,92 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+fn render_prompt(width: &str, config: toml::value::Value) -> io::Result<String> {
+    let output = common::render_prompt()
+        .arg("--cmd-duration=100")
+        .arg("--jobs=2")
+        .arg("--output=plain")
+        .arg(format!("--terminal-width={}", width))
+        .env("TEST_VAR", "value")
+        .use_config(config)
+        .output()?;
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+fn config() -> toml::value::Value {
+    toml::toml! {
+        add_newline = false
+        prompt_order = ["cmd_duration", "env_var", "jobs", "line_break", "character"]
+
+        [env_var]
+        variable = "TEST_VAR"
+    }
+}
+
+#[test]
+fn prompt_fitting_terminal_is_unchanged() -> io::Result<()> {
+    let actual = render_prompt("26", config())?;
+
+    assert_eq!("took 1m40s with value ✦2 \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn connecting_words_are_removed_first() -> io::Result<()> {
+    let actual = render_prompt("21", config())?;
+
+    assert_eq!("took 1m40s value ✦2 \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn modules_are_dropped_from_the_right() -> io::Result<()> {
+    let actual = render_prompt("15", config())?;
+
+    assert_eq!("took 1m40s \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn modules_are_dropped_by_priority() -> io::Result<()> {
+    let actual = render_prompt(
+        "15",
+        toml::toml! {
+            add_newline = false
+            prompt_order = ["cmd_duration", "env_var", "jobs", "line_break", "character"]
+            module_priority = ["env_var", "jobs"]
+
+            [env_var]
+            variable = "TEST_VAR"
+        },
+    )?;
+
+    assert_eq!("value ✦2 \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn lines_after_an_unfittable_line_are_fitted() -> io::Result<()> {
+    let actual = render_prompt(
+        "7",
+        toml::toml! {
+            add_newline = false
+            prompt_order = ["cmd_duration", "line_break", "env_var", "jobs", "line_break", "character"]
+
+            [env_var]
+            variable = "TEST_VAR"
+        },
+    )?;
+
+    assert_eq!("took 1m40s \nvalue \n❯ ", actual);
+    Ok(())
+}
+
+#[test]
+fn invalid_terminal_width_is_ignored() -> io::Result<()> {
+    let actual = render_prompt("", config())?;
+
+    assert_eq!("took 1m40s with value ✦2 \n❯ ", actual);
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use ansi_term::Color;
 use std::io;
 
 use crate::common::{self, TestCommand};
@@ -56,3 +57,21 @@ fn config_check_prefix_and_suffix() -> io::Result<()> {
     assert!(actual.ends_with(&col_suffix));
     Ok(())
 }
+
+#[test]
+fn time_format_with_format_string() -> io::Result<()> {
+    let output = common::render_module("time")
+        .use_config(toml::toml! {
+            [time]
+            disabled = false
+            time_format = "%Y"
+            format = "<$time>"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let year = chrono::Local::now().format("%Y").to_string();
+    let expected = format!("<{}>", Color::Yellow.bold().paint(year));
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn timings_lists_prompt_modules() -> io::Result<()> {
+    let output = common::starship_command(&["timings"])
+        .use_config(toml::toml! {
+            prompt_order = ["line_break", "character"]
+            right_prompt_order = ["jobs"]
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let lines = actual.lines().collect::<Vec<&str>>();
+
+    assert!(lines[0].starts_with("Module"));
+    let mut modules = lines[1..4]
+        .iter()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect::<Vec<&str>>();
+    modules.sort();
+    assert_eq!(vec!["character", "jobs", "line_break"], modules);
+    assert!(actual.contains(r#""\n""#));
+    assert!(actual.contains(r#""❯ ""#));
+    assert_eq!("", lines[4]);
+    assert!(lines[5].starts_with("Total: "));
+    Ok(())
+}
+
+#[test]
+fn timings_leaves_out_disabled_modules() -> io::Result<()> {
+    let output = common::starship_command(&["timings"])
+        .use_config(toml::toml! {
+            prompt_order = ["line_break", "character"]
+
+            [line_break]
+            disabled = true
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("character"));
+    assert!(!actual.contains("line_break"));
+    Ok(())
+}
@@ -0,0 +1,202 @@
+#![cfg(unix)]
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::{env, io, iter, process};
+
+use crate::common::{self, TestCommand};
+
+/// Creates a fake binary printing `output`, which logs every time it is run. It
+/// takes a moment like a real one, so that the binaries of parallel modules
+/// run at the same time.
+fn create_fake_binary(bin_dir: &Path, name: &str, output: &str) -> io::Result<()> {
+    let path = bin_dir.join(name);
+    let script = format!(
+        "#!/bin/sh\nsleep 0.1\necho run >> \"{}\"\necho \"{}\"\n",
+        bin_dir.join("runs.log").display(),
+        output
+    );
+    fs::write(&path, script)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+}
+
+/// Creates a fake `go` binary, which logs every time it is run
+fn create_fake_go(bin_dir: &Path, version: &str) -> io::Result<()> {
+    let output = format!("go version go{} linux/amd64", version);
+    create_fake_binary(bin_dir, "go", &output)
+}
+
+/// `PATH` with the fake binaries in front of the real ones
+fn path_with(bin_dir: &Path) -> OsString {
+    let paths = iter::once(bin_dir.to_path_buf()).chain(env::split_paths(env!("PATH")));
+    env::join_paths(paths).unwrap()
+}
+
+fn render_golang(project_dir: &Path, bin_dir: &Path, cache_dir: &Path) -> process::Command {
+    let mut command = common::render_module("golang");
+    command
+        .arg("--path")
+        .arg(project_dir)
+        .env("PATH", path_with(bin_dir))
+        .env("XDG_CACHE_HOME", cache_dir);
+    command
+}
+
+fn count_runs(bin_dir: &Path) -> usize {
+    fs::read_to_string(bin_dir.join("runs.log"))
+        .map(|log| log.lines().count())
+        .unwrap_or(0)
+}
+
+#[test]
+fn version_is_cached() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("main.go"))?;
+    create_fake_go(bin_dir.path(), "1.12.1")?;
+
+    for _ in 0..2 {
+        let output =
+            render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        assert!(actual.contains("v1.12.1"));
+    }
+
+    assert_eq!(count_runs(bin_dir.path()), 1);
+    Ok(())
+}
+
+#[test]
+fn changed_binary_is_run_again() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("main.go"))?;
+
+    create_fake_go(bin_dir.path(), "1.12.1")?;
+    render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+    create_fake_go(bin_dir.path(), "1.13.10")?;
+    let output = render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("v1.13.10"));
+    assert_eq!(count_runs(bin_dir.path()), 2);
+    Ok(())
+}
+
+#[test]
+fn go_toolchain_directive_is_part_of_key() -> io::Result<()> {
+    let project_a = common::new_tempdir()?;
+    let project_b = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    fs::write(
+        project_a.path().join("go.mod"),
+        "module a\n\ngo 1.21\n\ntoolchain go1.22.1\n",
+    )?;
+    fs::write(
+        project_b.path().join("go.mod"),
+        "module b\n\ngo 1.21\n\ntoolchain go1.21.5\n",
+    )?;
+    create_fake_go(bin_dir.path(), "1.21.0")?;
+
+    for project_dir in &[&project_a, &project_b, &project_a] {
+        render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+    }
+
+    assert_eq!(count_runs(bin_dir.path()), 2);
+    Ok(())
+}
+
+#[test]
+fn versions_of_parallel_modules_are_all_cached() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("main.go"))?;
+    File::create(project_dir.path().join("index.js"))?;
+    File::create(project_dir.path().join("Gemfile"))?;
+    create_fake_go(bin_dir.path(), "1.12.1")?;
+    create_fake_binary(bin_dir.path(), "node", "v12.0.0")?;
+    create_fake_binary(
+        bin_dir.path(),
+        "ruby",
+        "ruby 2.6.3p62 (2019-04-16 revision 67580)",
+    )?;
+
+    for _ in 0..2 {
+        common::render_prompt()
+            .arg("--path")
+            .arg(project_dir.path())
+            .env("PATH", path_with(bin_dir.path()))
+            .env("XDG_CACHE_HOME", cache_dir.path())
+            .env("RAYON_NUM_THREADS", "3")
+            .use_config(toml::toml! {
+                prompt_order = ["golang", "nodejs", "ruby"]
+            })
+            .output()?;
+    }
+
+    assert_eq!(count_runs(bin_dir.path()), 3);
+    Ok(())
+}
+
+#[test]
+fn toolchain_shims_are_not_cached() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let shims_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    let bin_dir = shims_dir.path().join("shims");
+    fs::create_dir(&bin_dir)?;
+    File::create(project_dir.path().join("main.go"))?;
+    create_fake_go(&bin_dir, "1.12.1")?;
+
+    for _ in 0..2 {
+        render_golang(project_dir.path(), &bin_dir, cache_dir.path()).output()?;
+    }
+
+    assert_eq!(count_runs(&bin_dir), 2);
+    Ok(())
+}
+
+#[test]
+fn cache_can_be_disabled() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("main.go"))?;
+    create_fake_go(bin_dir.path(), "1.12.1")?;
+
+    for _ in 0..2 {
+        render_golang(project_dir.path(), bin_dir.path(), cache_dir.path())
+            .use_config(toml::toml! {
+                version_cache = false
+            })
+            .output()?;
+    }
+
+    assert_eq!(count_runs(bin_dir.path()), 2);
+    Ok(())
+}
+
+#[test]
+fn cache_clear_removes_cached_versions() -> io::Result<()> {
+    let project_dir = common::new_tempdir()?;
+    let bin_dir = common::new_tempdir()?;
+    let cache_dir = common::new_tempdir()?;
+    File::create(project_dir.path().join("main.go"))?;
+    create_fake_go(bin_dir.path(), "1.12.1")?;
+
+    render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+    let output = common::starship_command(&["cache", "clear"])
+        .env("XDG_CACHE_HOME", cache_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    render_golang(project_dir.path(), bin_dir.path(), cache_dir.path()).output()?;
+
+    assert_eq!(count_runs(bin_dir.path()), 2);
+    Ok(())
+}